@@ -38,18 +38,57 @@ enum Tools {
     },
     URLDecode {
         data: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Decode '+' as a space, like form data"
+        )]
+        plus: bool,
     },
     URLEncode {
         data: Option<String>,
+        #[arg(
+            long,
+            default_value = "component",
+            value_parser = ["component", "path", "query", "form", "full"],
+            help = "What the string is, which decides the characters that get encoded"
+        )]
+        mode: String,
     },
     URLParse {
         data: Option<String>,
     },
     HTMLUnescape {
         data: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use the rules for attribute values, where '&copy=' isn't an entity"
+        )]
+        attribute: bool,
     },
     HTMLEscape {
         data: Option<String>,
+        #[arg(
+            long,
+            default_value = "attribute",
+            value_parser = ["text", "attribute", "script", "style", "url"],
+            help = "Where the string goes, which decides what gets escaped"
+        )]
+        context: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "numeric",
+            help = "Use named entities like &eacute; for everything outside ASCII"
+        )]
+        named: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use numeric entities like &#xE9; for everything outside ASCII"
+        )]
+        numeric: bool,
     },
     JWTDecode {
         data: Option<String>,
@@ -61,6 +100,28 @@ enum Tools {
     FormatJSON {
         #[arg(help = "Enter JSON data and then type 'END' on a new line to stop input")]
         data: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Format every line as a separate document"
+        )]
+        ndjson: bool,
+        #[arg(long, default_value_t = false, help = "Output compact JSON")]
+        minify: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "split",
+            help = "Collect every line into a single array"
+        )]
+        slurp: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "ndjson",
+            help = "Split an array into one line per item"
+        )]
+        split: bool,
     },
     FormatYAML {
         #[arg(help = "Enter YAML data and then type 'END' on a new line to stop input")]
@@ -69,11 +130,668 @@ enum Tools {
     YamlToJSON {
         #[arg(help = "Enter YAML data and then type 'END' on a new line to stop input")]
         data: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Output every YAML document as one line of JSON"
+        )]
+        ndjson: bool,
     },
     TomlToJSON {
         #[arg(help = "Enter TOML data and then type 'END' on a new line to stop input")]
         data: Option<String>,
     },
+    JSONQuery {
+        #[arg(help = "A JSONPath expression (starting with '$') or a jq filter")]
+        expr: Option<String>,
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Output strings without quotes"
+        )]
+        raw: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Query every line of JSON separately"
+        )]
+        ndjson: bool,
+    },
+    Diff {
+        #[arg(help = "Path to the original JSON, YAML or TOML document")]
+        left: Option<String>,
+        #[arg(help = "Path to the JSON, YAML or TOML document to compare against")]
+        right: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Output the differences as a JSON Patch"
+        )]
+        patch: bool,
+    },
+    JSONPatch {
+        #[arg(help = "Path to a JSON Patch (RFC 6902) file")]
+        patch: Option<String>,
+        #[arg(help = "Enter JSON or YAML data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    JSONMergePatch {
+        #[arg(help = "Path to a JSON Merge Patch (RFC 7396) file")]
+        patch: Option<String>,
+        #[arg(help = "Enter JSON or YAML data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    JSONValidate {
+        #[arg(short, long, help = "Path to the JSON Schema file")]
+        schema: Option<String>,
+        #[arg(
+            long,
+            value_parser = ["7", "2020-12"],
+            help = "The JSON Schema draft to use, detected from $schema when not given"
+        )]
+        draft: Option<String>,
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    JSONSchemaInfer {
+        #[arg(help = "Paths to JSON, YAML or NDJSON sample files")]
+        files: Vec<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Treat every line as a separate sample"
+        )]
+        ndjson: bool,
+    },
+    JSONToTypes {
+        #[arg(
+            short,
+            long,
+            default_value = "rust",
+            value_parser = ["rust", "ts", "go", "python"],
+            help = "The language to generate types for"
+        )]
+        lang: String,
+        #[arg(long, default_value = "Root", help = "Name of the top level type")]
+        name: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Generate Python TypedDicts instead of dataclasses"
+        )]
+        typed_dict: bool,
+        #[arg(help = "Enter JSON or YAML data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    JSONFlatten {
+        #[arg(
+            short,
+            long,
+            default_value = ".",
+            help = "Separator placed between keys"
+        )]
+        separator: String,
+        #[arg(
+            long,
+            default_value = "index",
+            value_parser = ["index", "bracket"],
+            help = "Write array indexes as keys (a.0) or in brackets (a[0])"
+        )]
+        arrays: String,
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    JSONUnflatten {
+        #[arg(
+            short,
+            long,
+            default_value = ".",
+            help = "Separator placed between keys"
+        )]
+        separator: String,
+        #[arg(
+            long,
+            default_value = "index",
+            value_parser = ["index", "bracket"],
+            help = "Read array indexes from keys (a.0) or from brackets (a[0])"
+        )]
+        arrays: String,
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    EnvToJSON {
+        #[arg(help = "Enter .env data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    IniToJSON {
+        #[arg(help = "Enter INI data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    PropertiesToJSON {
+        #[arg(help = "Enter Java properties data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+    },
+    JSONToEnv {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    JSONToIni {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    JSONToProperties {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+    },
+    MsgpackToJSON {
+        #[arg(help = "MessagePack data encoded as hex or Base64")]
+        data: Option<String>,
+        #[arg(short, long, help = "Read raw MessagePack bytes from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "auto",
+            value_parser = ["auto", "hex", "base64"],
+            help = "How the data is encoded, detected when set to auto"
+        )]
+        encoding: String,
+    },
+    CborToJSON {
+        #[arg(help = "CBOR data encoded as hex or Base64")]
+        data: Option<String>,
+        #[arg(short, long, help = "Read raw CBOR bytes from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "auto",
+            value_parser = ["auto", "hex", "base64"],
+            help = "How the data is encoded, detected when set to auto"
+        )]
+        encoding: String,
+    },
+    BSONToJSON {
+        #[arg(help = "BSON data encoded as hex or Base64")]
+        data: Option<String>,
+        #[arg(short, long, help = "Read raw BSON bytes from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "auto",
+            value_parser = ["auto", "hex", "base64"],
+            help = "How the data is encoded, detected when set to auto"
+        )]
+        encoding: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Write canonical extended JSON with every number type marked"
+        )]
+        canonical: bool,
+    },
+    JSONToMsgpack {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "base64",
+            value_parser = ["base64", "hex"],
+            help = "How to encode the MessagePack output"
+        )]
+        encoding: String,
+        #[arg(short, long, help = "Write the raw MessagePack bytes to a file")]
+        out: Option<String>,
+    },
+    JSONToCbor {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "base64",
+            value_parser = ["base64", "hex"],
+            help = "How to encode the CBOR output"
+        )]
+        encoding: String,
+        #[arg(short, long, help = "Write the raw CBOR bytes to a file")]
+        out: Option<String>,
+    },
+    JSONToBSON {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "base64",
+            value_parser = ["base64", "hex"],
+            help = "How to encode the BSON output"
+        )]
+        encoding: String,
+        #[arg(short, long, help = "Write the raw BSON bytes to a file")]
+        out: Option<String>,
+    },
+    ProtobufDecode {
+        #[arg(help = "Protobuf data encoded as hex or Base64")]
+        data: Option<String>,
+        #[arg(short, long, help = "Read raw protobuf bytes from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "auto",
+            value_parser = ["auto", "hex", "base64"],
+            help = "How the data is encoded, detected when set to auto"
+        )]
+        encoding: String,
+        #[arg(
+            long,
+            help = "Path to a .proto file or descriptor set used to name the fields"
+        )]
+        proto: Option<String>,
+        #[arg(
+            short,
+            long,
+            help = "The message type to decode as, defaults to the first one in the schema"
+        )]
+        message: Option<String>,
+    },
+    HexEncode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Use upper case hex digits"
+        )]
+        upper: bool,
+        #[arg(short, long, help = "Separator placed between bytes, e.g. ':' or ' '")]
+        separator: Option<String>,
+        #[arg(
+            long,
+            default_value = "hex",
+            value_parser = ["hex", "octal", "binary"],
+            help = "Write each byte in hex, octal or binary"
+        )]
+        base: String,
+    },
+    HexDecode {
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "hex",
+            value_parser = ["hex", "octal", "binary"],
+            help = "Read each byte as hex, octal or binary"
+        )]
+        base: String,
+        #[arg(long, default_value_t = false, help = "Print the decoded bytes as hex")]
+        hex: bool,
+        #[arg(short, long, help = "Write the decoded bytes to a file")]
+        out: Option<String>,
+    },
+    Hexdump {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to dump from a file")]
+        file: Option<String>,
+        #[arg(short, long, default_value_t = 16, help = "Bytes per line")]
+        width: usize,
+    },
+    Base32Encode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "rfc4648",
+            value_parser = ["rfc4648", "hex", "crockford"],
+            help = "The Base32 alphabet to use"
+        )]
+        alphabet: String,
+        #[arg(long, default_value_t = false, help = "Leave out the '=' padding")]
+        no_pad: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use a Crockford check symbol as the last character"
+        )]
+        check: bool,
+    },
+    Base32Decode {
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "rfc4648",
+            value_parser = ["rfc4648", "hex", "crockford"],
+            help = "The Base32 alphabet to use"
+        )]
+        alphabet: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use a Crockford check symbol as the last character"
+        )]
+        check: bool,
+        #[arg(long, default_value_t = false, help = "Print the decoded bytes as hex")]
+        hex: bool,
+        #[arg(short, long, help = "Write the decoded bytes to a file")]
+        out: Option<String>,
+    },
+    Base58Encode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "bitcoin",
+            value_parser = ["bitcoin", "flickr", "ripple"],
+            help = "The Base58 alphabet to use"
+        )]
+        alphabet: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use Base58Check, with a 4 byte double SHA-256 checksum"
+        )]
+        check: bool,
+    },
+    Base58Decode {
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "bitcoin",
+            value_parser = ["bitcoin", "flickr", "ripple"],
+            help = "The Base58 alphabet to use"
+        )]
+        alphabet: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Use Base58Check, with a 4 byte double SHA-256 checksum"
+        )]
+        check: bool,
+        #[arg(long, default_value_t = false, help = "Print the decoded bytes as hex")]
+        hex: bool,
+        #[arg(short, long, help = "Write the decoded bytes to a file")]
+        out: Option<String>,
+    },
+    Base85Encode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "ascii85",
+            value_parser = ["ascii85", "z85"],
+            help = "The Base85 variant to use"
+        )]
+        variant: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Wrap Ascii85 output in <~ and ~>"
+        )]
+        delimiters: bool,
+    },
+    Base85Decode {
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "ascii85",
+            value_parser = ["ascii85", "z85"],
+            help = "The Base85 variant to use"
+        )]
+        variant: String,
+        #[arg(long, default_value_t = false, help = "Print the decoded bytes as hex")]
+        hex: bool,
+        #[arg(short, long, help = "Write the decoded bytes to a file")]
+        out: Option<String>,
+    },
+    DataURIEncode {
+        #[arg(help = "Path of the file to encode")]
+        file: Option<String>,
+        #[arg(long, help = "The MIME type to use instead of detecting it")]
+        mime: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Percent-encode instead of Base64, usually smaller for SVG and other text"
+        )]
+        percent: bool,
+    },
+    DataURIDecode {
+        data: Option<String>,
+        #[arg(short, long, help = "Write the payload to a file")]
+        out: Option<String>,
+    },
+    QuotedPrintableEncode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(long, default_value = "utf-8", help = "The charset of the text")]
+        charset: String,
+    },
+    QuotedPrintableDecode {
+        #[arg(help = "Enter the data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+        #[arg(long, default_value = "utf-8", help = "The charset of the text")]
+        charset: String,
+    },
+    EncodedWordEncode {
+        data: Option<String>,
+        #[arg(long, default_value = "utf-8", help = "The charset of the text")]
+        charset: String,
+        #[arg(
+            long,
+            default_value = "b",
+            value_parser = ["b", "q"],
+            help = "Use the B (Base64) or Q (quoted-printable) encoding"
+        )]
+        encoding: String,
+    },
+    EncodedWordDecode {
+        data: Option<String>,
+    },
+    Uuencode {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to encode from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            help = "File name for the begin line, defaults to the input file's name"
+        )]
+        name: Option<String>,
+    },
+    Uudecode {
+        #[arg(help = "Enter the data and then type 'END' on a new line to stop input")]
+        data: Option<String>,
+        #[arg(long, default_value_t = false, help = "Print the decoded bytes as hex")]
+        hex: bool,
+        #[arg(short, long, help = "Write the decoded bytes to a file")]
+        out: Option<String>,
+    },
+    URLEdit {
+        data: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            help = "Set a query parameter, replacing any existing values"
+        )]
+        set_param: Vec<String>,
+        #[arg(
+            long,
+            value_name = "KEY",
+            help = "Remove every value of a query parameter"
+        )]
+        remove_param: Vec<String>,
+        #[arg(long, help = "Replace the scheme")]
+        scheme: Option<String>,
+        #[arg(long, help = "Replace the host")]
+        host: Option<String>,
+        #[arg(long, help = "Replace the path")]
+        path: Option<String>,
+        #[arg(
+            long,
+            help = "Resolve a relative URL like '../other' against the URL first"
+        )]
+        join: Option<String>,
+    },
+    QueryToJSON {
+        #[arg(help = "A query string or a URL")]
+        data: Option<String>,
+    },
+    JSONToQuery {
+        #[arg(
+            help = "Enter JSON, YAML or TOML data and then type 'END' on a new line to stop input"
+        )]
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "brackets",
+            value_parser = ["brackets", "dots"],
+            help = "Write nested keys as a[b][c] or a.b.c"
+        )]
+        style: String,
+        #[arg(
+            long,
+            default_value = "brackets",
+            value_parser = ["brackets", "indices", "repeat"],
+            help = "Write arrays as a[]=1&a[]=2, a[0]=1&a[1]=2 or a=1&a=2"
+        )]
+        arrays: String,
+    },
+    IdnaEncode {
+        data: Option<String>,
+    },
+    IdnaDecode {
+        data: Option<String>,
+    },
+    Escape {
+        data: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "json",
+            value_parser = ["json", "c", "rust", "python", "java", "shell", "powershell", "sql", "regex", "csv", "xml"],
+            help = "The language to write a string literal for"
+        )]
+        lang: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Escape everything outside ASCII as well"
+        )]
+        ascii: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "ascii",
+            help = "Write a raw string for C++, Rust or Python"
+        )]
+        raw: bool,
+    },
+    Unescape {
+        data: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "json",
+            value_parser = ["json", "c", "rust", "python", "java", "shell", "powershell", "sql", "regex", "csv", "xml"],
+            help = "The language the string literal is written in"
+        )]
+        lang: String,
+    },
+    InspectString {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to inspect from a file")]
+        file: Option<String>,
+    },
+    CharsetConvert {
+        data: Option<String>,
+        #[arg(short, long, help = "Read the bytes to convert from a file")]
+        file: Option<String>,
+        #[arg(
+            long,
+            default_value = "auto",
+            help = "The charset the data is in, e.g. windows-1252 or shift_jis. 'auto' detects it"
+        )]
+        from: String,
+        #[arg(long, default_value = "utf-8", help = "The charset to convert to")]
+        to: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Only detect the charset of the data"
+        )]
+        detect: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Repair UTF-8 text that was read as another charset, like 'Ã©' for 'é'. --from names that charset"
+        )]
+        fix_mojibake: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Print the converted bytes as hex"
+        )]
+        hex: bool,
+        #[arg(short, long, help = "Write the converted bytes to a file")]
+        out: Option<String>,
+    },
+    UnicodeNormalize {
+        data: Option<String>,
+        #[arg(
+            long,
+            default_value = "nfc",
+            value_parser = ["nfc", "nfd", "nfkc", "nfkd"],
+            help = "The normalization form. macOS file names are mostly NFD, while most other text is NFC"
+        )]
+        form: String,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Fold case for caseless comparison, e.g. 'Straße' becomes 'strasse'"
+        )]
+        case_fold: bool,
+        #[arg(
+            long,
+            value_parser = ["half", "full"],
+            help = "Convert ASCII, spaces and katakana to their half-width or full-width forms"
+        )]
+        width: Option<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Remove accents and other diacritics, e.g. 'Crème brûlée' becomes 'Creme brulee'"
+        )]
+        strip_diacritics: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Only report whether the text is already normalized"
+        )]
+        check: bool,
+    },
 }
 
 impl fmt::Display for Tools {
@@ -81,20 +799,69 @@ impl fmt::Display for Tools {
         match self {
             Tools::Base64Decode { data: _ } => write!(f, "base64-decode"),
             Tools::Base64Encode { data: _ } => write!(f, "base64-encode"),
-            Tools::URLDecode { data: _ } => write!(f, "url-decode"),
-            Tools::URLEncode { data: _ } => write!(f, "url-encode"),
+            Tools::URLDecode { .. } => write!(f, "url-decode"),
+            Tools::URLEncode { .. } => write!(f, "url-encode"),
             Tools::URLParse { data: _ } => write!(f, "url-parse"),
-            Tools::HTMLUnescape { data: _ } => write!(f, "html-unescape"),
-            Tools::HTMLEscape { data: _ } => write!(f, "html-escape"),
+            Tools::HTMLUnescape { .. } => write!(f, "html-unescape"),
+            Tools::HTMLEscape { .. } => write!(f, "html-escape"),
             Tools::JWTDecode {
                 data: _,
                 header: _,
                 payload: _,
             } => write!(f, "jwt-decode"),
-            Tools::FormatJSON { data: _ } => write!(f, "format-json"),
+            Tools::FormatJSON { .. } => write!(f, "format-json"),
             Tools::FormatYAML { data: _ } => write!(f, "format-yaml"),
-            Tools::YamlToJSON { data: _ } => write!(f, "yaml-to-json"),
+            Tools::YamlToJSON { .. } => write!(f, "yaml-to-json"),
             Tools::TomlToJSON { data: _ } => write!(f, "toml-to-json"),
+            Tools::JSONQuery { .. } => write!(f, "json-query"),
+            Tools::Diff { .. } => write!(f, "diff"),
+            Tools::JSONPatch { .. } => write!(f, "json-patch"),
+            Tools::JSONMergePatch { .. } => write!(f, "json-merge-patch"),
+            Tools::JSONValidate { .. } => write!(f, "json-validate"),
+            Tools::JSONSchemaInfer { .. } => write!(f, "json-schema-infer"),
+            Tools::JSONToTypes { .. } => write!(f, "json-to-types"),
+            Tools::JSONFlatten { .. } => write!(f, "json-flatten"),
+            Tools::JSONUnflatten { .. } => write!(f, "json-unflatten"),
+            Tools::EnvToJSON { data: _ } => write!(f, "env-to-json"),
+            Tools::IniToJSON { data: _ } => write!(f, "ini-to-json"),
+            Tools::PropertiesToJSON { data: _ } => write!(f, "properties-to-json"),
+            Tools::JSONToEnv { data: _ } => write!(f, "json-to-env"),
+            Tools::JSONToIni { data: _ } => write!(f, "json-to-ini"),
+            Tools::JSONToProperties { data: _ } => write!(f, "json-to-properties"),
+            Tools::MsgpackToJSON { .. } => write!(f, "msgpack-to-json"),
+            Tools::CborToJSON { .. } => write!(f, "cbor-to-json"),
+            Tools::BSONToJSON { .. } => write!(f, "bson-to-json"),
+            Tools::JSONToMsgpack { .. } => write!(f, "json-to-msgpack"),
+            Tools::JSONToCbor { .. } => write!(f, "json-to-cbor"),
+            Tools::JSONToBSON { .. } => write!(f, "json-to-bson"),
+            Tools::ProtobufDecode { .. } => write!(f, "protobuf-decode"),
+            Tools::HexEncode { .. } => write!(f, "hex-encode"),
+            Tools::HexDecode { .. } => write!(f, "hex-decode"),
+            Tools::Hexdump { .. } => write!(f, "hexdump"),
+            Tools::Base32Encode { .. } => write!(f, "base32-encode"),
+            Tools::Base32Decode { .. } => write!(f, "base32-decode"),
+            Tools::Base58Encode { .. } => write!(f, "base58-encode"),
+            Tools::Base58Decode { .. } => write!(f, "base58-decode"),
+            Tools::Base85Encode { .. } => write!(f, "base85-encode"),
+            Tools::Base85Decode { .. } => write!(f, "base85-decode"),
+            Tools::DataURIEncode { .. } => write!(f, "data-uri-encode"),
+            Tools::DataURIDecode { .. } => write!(f, "data-uri-decode"),
+            Tools::QuotedPrintableEncode { .. } => write!(f, "quoted-printable-encode"),
+            Tools::QuotedPrintableDecode { .. } => write!(f, "quoted-printable-decode"),
+            Tools::EncodedWordEncode { .. } => write!(f, "encoded-word-encode"),
+            Tools::EncodedWordDecode { .. } => write!(f, "encoded-word-decode"),
+            Tools::Uuencode { .. } => write!(f, "uuencode"),
+            Tools::Uudecode { .. } => write!(f, "uudecode"),
+            Tools::URLEdit { .. } => write!(f, "url-edit"),
+            Tools::QueryToJSON { .. } => write!(f, "query-to-json"),
+            Tools::JSONToQuery { .. } => write!(f, "json-to-query"),
+            Tools::IdnaEncode { .. } => write!(f, "idna-encode"),
+            Tools::IdnaDecode { .. } => write!(f, "idna-decode"),
+            Tools::Escape { .. } => write!(f, "escape"),
+            Tools::Unescape { .. } => write!(f, "unescape"),
+            Tools::InspectString { .. } => write!(f, "inspect-string"),
+            Tools::CharsetConvert { .. } => write!(f, "charset-convert"),
+            Tools::UnicodeNormalize { .. } => write!(f, "unicode-normalize"),
         }
     }
 }
@@ -105,20 +872,69 @@ fn main() {
     let tool_data: &Option<String> = match &args.tool {
         Some(Tools::Base64Decode { data }) => data,
         Some(Tools::Base64Encode { data }) => data,
-        Some(Tools::URLDecode { data }) => data,
-        Some(Tools::URLEncode { data }) => data,
+        Some(Tools::URLDecode { data, .. }) => data,
+        Some(Tools::URLEncode { data, .. }) => data,
         Some(Tools::URLParse { data }) => data,
-        Some(Tools::HTMLUnescape { data }) => data,
-        Some(Tools::HTMLEscape { data }) => data,
+        Some(Tools::HTMLUnescape { data, .. }) => data,
+        Some(Tools::HTMLEscape { data, .. }) => data,
         Some(Tools::JWTDecode {
             data,
             header: _,
             payload: _,
         }) => data,
-        Some(Tools::FormatJSON { data }) => data,
+        Some(Tools::FormatJSON { data, .. }) => data,
         Some(Tools::FormatYAML { data }) => data,
-        Some(Tools::YamlToJSON { data }) => data,
+        Some(Tools::YamlToJSON { data, .. }) => data,
         Some(Tools::TomlToJSON { data }) => data,
+        Some(Tools::JSONQuery { data, .. }) => data,
+        Some(Tools::Diff { .. }) => &None,
+        Some(Tools::JSONPatch { data, .. }) => data,
+        Some(Tools::JSONMergePatch { data, .. }) => data,
+        Some(Tools::JSONValidate { data, .. }) => data,
+        Some(Tools::JSONSchemaInfer { .. }) => &None,
+        Some(Tools::JSONToTypes { data, .. }) => data,
+        Some(Tools::JSONFlatten { data, .. }) => data,
+        Some(Tools::JSONUnflatten { data, .. }) => data,
+        Some(Tools::EnvToJSON { data }) => data,
+        Some(Tools::IniToJSON { data }) => data,
+        Some(Tools::PropertiesToJSON { data }) => data,
+        Some(Tools::JSONToEnv { data }) => data,
+        Some(Tools::JSONToIni { data }) => data,
+        Some(Tools::JSONToProperties { data }) => data,
+        Some(Tools::MsgpackToJSON { data, .. }) => data,
+        Some(Tools::CborToJSON { data, .. }) => data,
+        Some(Tools::BSONToJSON { data, .. }) => data,
+        Some(Tools::JSONToMsgpack { data, .. }) => data,
+        Some(Tools::JSONToCbor { data, .. }) => data,
+        Some(Tools::JSONToBSON { data, .. }) => data,
+        Some(Tools::ProtobufDecode { data, .. }) => data,
+        Some(Tools::HexEncode { data, .. }) => data,
+        Some(Tools::HexDecode { data, .. }) => data,
+        Some(Tools::Hexdump { data, .. }) => data,
+        Some(Tools::Base32Encode { data, .. }) => data,
+        Some(Tools::Base32Decode { data, .. }) => data,
+        Some(Tools::Base58Encode { data, .. }) => data,
+        Some(Tools::Base58Decode { data, .. }) => data,
+        Some(Tools::Base85Encode { data, .. }) => data,
+        Some(Tools::Base85Decode { data, .. }) => data,
+        Some(Tools::DataURIEncode { .. }) => &None,
+        Some(Tools::DataURIDecode { data, .. }) => data,
+        Some(Tools::QuotedPrintableEncode { data, .. }) => data,
+        Some(Tools::QuotedPrintableDecode { data, .. }) => data,
+        Some(Tools::EncodedWordEncode { data, .. }) => data,
+        Some(Tools::EncodedWordDecode { data, .. }) => data,
+        Some(Tools::Uuencode { data, .. }) => data,
+        Some(Tools::Uudecode { data, .. }) => data,
+        Some(Tools::URLEdit { data, .. }) => data,
+        Some(Tools::QueryToJSON { data }) => data,
+        Some(Tools::JSONToQuery { data, .. }) => data,
+        Some(Tools::IdnaEncode { data }) => data,
+        Some(Tools::IdnaDecode { data }) => data,
+        Some(Tools::Escape { data, .. }) => data,
+        Some(Tools::Unescape { data, .. }) => data,
+        Some(Tools::InspectString { data, .. }) => data,
+        Some(Tools::CharsetConvert { data, .. }) => data,
+        Some(Tools::UnicodeNormalize { data, .. }) => data,
         None => &None,
     };
     let header_flag: bool = match &args.tool {
@@ -158,6 +974,7 @@ fn main() {
                 message: "URL Decode",
                 arg_name: Some(Tools::URLDecode {
                     data: tool_data.clone(),
+                    plus: false,
                 }),
                 handle: url_decode,
             },
@@ -165,6 +982,7 @@ fn main() {
                 message: "URL Encode",
                 arg_name: Some(Tools::URLEncode {
                     data: tool_data.clone(),
+                    mode: "component".to_string(),
                 }),
                 handle: url_encode,
             },
@@ -179,6 +997,7 @@ fn main() {
                 message: "HTML Unescape",
                 arg_name: Some(Tools::HTMLUnescape {
                     data: tool_data.clone(),
+                    attribute: false,
                 }),
                 handle: html_unescape,
             },
@@ -186,6 +1005,9 @@ fn main() {
                 message: "HTML Escape",
                 arg_name: Some(Tools::HTMLEscape {
                     data: tool_data.clone(),
+                    context: "attribute".to_string(),
+                    named: false,
+                    numeric: false,
                 }),
                 handle: html_escape,
             },
@@ -202,6 +1024,10 @@ fn main() {
                 message: "Format JSON",
                 arg_name: Some(Tools::FormatJSON {
                     data: tool_data.clone(),
+                    ndjson: false,
+                    minify: false,
+                    slurp: false,
+                    split: false,
                 }),
                 handle: format_json,
             },
@@ -216,6 +1042,7 @@ fn main() {
                 message: "YAML to JSON",
                 arg_name: Some(Tools::YamlToJSON {
                     data: tool_data.clone(),
+                    ndjson: false,
                 }),
                 handle: yaml_to_json,
             },
@@ -226,6 +1053,450 @@ fn main() {
                 }),
                 handle: toml_to_json,
             },
+            SelectOption {
+                message: "JSON Query",
+                arg_name: Some(Tools::JSONQuery {
+                    expr: None,
+                    data: tool_data.clone(),
+                    raw: false,
+                    ndjson: false,
+                }),
+                handle: json_query,
+            },
+            SelectOption {
+                message: "Diff",
+                arg_name: Some(Tools::Diff {
+                    left: None,
+                    right: None,
+                    patch: false,
+                }),
+                handle: diff,
+            },
+            SelectOption {
+                message: "JSON Patch",
+                arg_name: Some(Tools::JSONPatch {
+                    patch: None,
+                    data: tool_data.clone(),
+                }),
+                handle: json_patch,
+            },
+            SelectOption {
+                message: "JSON Merge Patch",
+                arg_name: Some(Tools::JSONMergePatch {
+                    patch: None,
+                    data: tool_data.clone(),
+                }),
+                handle: json_merge_patch,
+            },
+            SelectOption {
+                message: "JSON Validate",
+                arg_name: Some(Tools::JSONValidate {
+                    schema: None,
+                    draft: None,
+                    data: tool_data.clone(),
+                }),
+                handle: json_validate,
+            },
+            SelectOption {
+                message: "JSON Schema Infer",
+                arg_name: Some(Tools::JSONSchemaInfer {
+                    files: vec![],
+                    ndjson: false,
+                }),
+                handle: json_schema_infer,
+            },
+            SelectOption {
+                message: "JSON to Types",
+                arg_name: Some(Tools::JSONToTypes {
+                    lang: "rust".to_string(),
+                    name: "Root".to_string(),
+                    typed_dict: false,
+                    data: tool_data.clone(),
+                }),
+                handle: json_to_types,
+            },
+            SelectOption {
+                message: "JSON Flatten",
+                arg_name: Some(Tools::JSONFlatten {
+                    separator: ".".to_string(),
+                    arrays: "index".to_string(),
+                    data: tool_data.clone(),
+                }),
+                handle: json_flatten,
+            },
+            SelectOption {
+                message: "JSON Unflatten",
+                arg_name: Some(Tools::JSONUnflatten {
+                    separator: ".".to_string(),
+                    arrays: "index".to_string(),
+                    data: tool_data.clone(),
+                }),
+                handle: json_unflatten,
+            },
+            SelectOption {
+                message: "ENV to JSON",
+                arg_name: Some(Tools::EnvToJSON {
+                    data: tool_data.clone(),
+                }),
+                handle: env_to_json,
+            },
+            SelectOption {
+                message: "INI to JSON",
+                arg_name: Some(Tools::IniToJSON {
+                    data: tool_data.clone(),
+                }),
+                handle: ini_to_json,
+            },
+            SelectOption {
+                message: "Properties to JSON",
+                arg_name: Some(Tools::PropertiesToJSON {
+                    data: tool_data.clone(),
+                }),
+                handle: properties_to_json,
+            },
+            SelectOption {
+                message: "JSON to ENV",
+                arg_name: Some(Tools::JSONToEnv {
+                    data: tool_data.clone(),
+                }),
+                handle: json_to_env,
+            },
+            SelectOption {
+                message: "JSON to INI",
+                arg_name: Some(Tools::JSONToIni {
+                    data: tool_data.clone(),
+                }),
+                handle: json_to_ini,
+            },
+            SelectOption {
+                message: "JSON to Properties",
+                arg_name: Some(Tools::JSONToProperties {
+                    data: tool_data.clone(),
+                }),
+                handle: json_to_properties,
+            },
+            SelectOption {
+                message: "MessagePack to JSON",
+                arg_name: Some(Tools::MsgpackToJSON {
+                    data: tool_data.clone(),
+                    file: None,
+                    encoding: "auto".to_string(),
+                }),
+                handle: msgpack_to_json,
+            },
+            SelectOption {
+                message: "CBOR to JSON",
+                arg_name: Some(Tools::CborToJSON {
+                    data: tool_data.clone(),
+                    file: None,
+                    encoding: "auto".to_string(),
+                }),
+                handle: cbor_to_json,
+            },
+            SelectOption {
+                message: "BSON to JSON",
+                arg_name: Some(Tools::BSONToJSON {
+                    data: tool_data.clone(),
+                    file: None,
+                    encoding: "auto".to_string(),
+                    canonical: false,
+                }),
+                handle: bson_to_json,
+            },
+            SelectOption {
+                message: "JSON to MessagePack",
+                arg_name: Some(Tools::JSONToMsgpack {
+                    data: tool_data.clone(),
+                    encoding: "base64".to_string(),
+                    out: None,
+                }),
+                handle: json_to_msgpack,
+            },
+            SelectOption {
+                message: "JSON to CBOR",
+                arg_name: Some(Tools::JSONToCbor {
+                    data: tool_data.clone(),
+                    encoding: "base64".to_string(),
+                    out: None,
+                }),
+                handle: json_to_cbor,
+            },
+            SelectOption {
+                message: "JSON to BSON",
+                arg_name: Some(Tools::JSONToBSON {
+                    data: tool_data.clone(),
+                    encoding: "base64".to_string(),
+                    out: None,
+                }),
+                handle: json_to_bson,
+            },
+            SelectOption {
+                message: "Protobuf Decode",
+                arg_name: Some(Tools::ProtobufDecode {
+                    data: tool_data.clone(),
+                    file: None,
+                    encoding: "auto".to_string(),
+                    proto: None,
+                    message: None,
+                }),
+                handle: protobuf_decode,
+            },
+            SelectOption {
+                message: "Hex Encode",
+                arg_name: Some(Tools::HexEncode {
+                    data: tool_data.clone(),
+                    file: None,
+                    upper: false,
+                    separator: None,
+                    base: "hex".to_string(),
+                }),
+                handle: hex_encode,
+            },
+            SelectOption {
+                message: "Hex Decode",
+                arg_name: Some(Tools::HexDecode {
+                    data: tool_data.clone(),
+                    base: "hex".to_string(),
+                    hex: false,
+                    out: None,
+                }),
+                handle: hex_decode,
+            },
+            SelectOption {
+                message: "Hexdump",
+                arg_name: Some(Tools::Hexdump {
+                    data: tool_data.clone(),
+                    file: None,
+                    width: 16,
+                }),
+                handle: hexdump,
+            },
+            SelectOption {
+                message: "Base32 Encode",
+                arg_name: Some(Tools::Base32Encode {
+                    data: tool_data.clone(),
+                    alphabet: "rfc4648".to_string(),
+                    file: None,
+                    no_pad: false,
+                    check: false,
+                }),
+                handle: base_32_encode,
+            },
+            SelectOption {
+                message: "Base32 Decode",
+                arg_name: Some(Tools::Base32Decode {
+                    data: tool_data.clone(),
+                    alphabet: "rfc4648".to_string(),
+                    check: false,
+                    hex: false,
+                    out: None,
+                }),
+                handle: base_32_decode,
+            },
+            SelectOption {
+                message: "Base58 Encode",
+                arg_name: Some(Tools::Base58Encode {
+                    data: tool_data.clone(),
+                    file: None,
+                    alphabet: "bitcoin".to_string(),
+                    check: false,
+                }),
+                handle: base_58_encode,
+            },
+            SelectOption {
+                message: "Base58 Decode",
+                arg_name: Some(Tools::Base58Decode {
+                    data: tool_data.clone(),
+                    alphabet: "bitcoin".to_string(),
+                    check: false,
+                    hex: false,
+                    out: None,
+                }),
+                handle: base_58_decode,
+            },
+            SelectOption {
+                message: "Base85 Encode",
+                arg_name: Some(Tools::Base85Encode {
+                    data: tool_data.clone(),
+                    file: None,
+                    variant: "ascii85".to_string(),
+                    delimiters: false,
+                }),
+                handle: base_85_encode,
+            },
+            SelectOption {
+                message: "Base85 Decode",
+                arg_name: Some(Tools::Base85Decode {
+                    data: tool_data.clone(),
+                    variant: "ascii85".to_string(),
+                    hex: false,
+                    out: None,
+                }),
+                handle: base_85_decode,
+            },
+            SelectOption {
+                message: "Data URI Encode",
+                arg_name: Some(Tools::DataURIEncode {
+                    file: None,
+                    mime: None,
+                    percent: false,
+                }),
+                handle: data_uri_encode,
+            },
+            SelectOption {
+                message: "Data URI Decode",
+                arg_name: Some(Tools::DataURIDecode {
+                    data: tool_data.clone(),
+                    out: None,
+                }),
+                handle: data_uri_decode,
+            },
+            SelectOption {
+                message: "Quoted-Printable Encode",
+                arg_name: Some(Tools::QuotedPrintableEncode {
+                    data: tool_data.clone(),
+                    file: None,
+                    charset: "utf-8".to_string(),
+                }),
+                handle: quoted_printable_encode,
+            },
+            SelectOption {
+                message: "Quoted-Printable Decode",
+                arg_name: Some(Tools::QuotedPrintableDecode {
+                    data: tool_data.clone(),
+                    charset: "utf-8".to_string(),
+                }),
+                handle: quoted_printable_decode,
+            },
+            SelectOption {
+                message: "Encoded Word Encode",
+                arg_name: Some(Tools::EncodedWordEncode {
+                    data: tool_data.clone(),
+                    charset: "utf-8".to_string(),
+                    encoding: "b".to_string(),
+                }),
+                handle: encoded_word_encode,
+            },
+            SelectOption {
+                message: "Encoded Word Decode",
+                arg_name: Some(Tools::EncodedWordDecode {
+                    data: tool_data.clone(),
+                }),
+                handle: encoded_word_decode,
+            },
+            SelectOption {
+                message: "Uuencode",
+                arg_name: Some(Tools::Uuencode {
+                    data: tool_data.clone(),
+                    file: None,
+                    name: None,
+                }),
+                handle: uuencode,
+            },
+            SelectOption {
+                message: "Uudecode",
+                arg_name: Some(Tools::Uudecode {
+                    data: tool_data.clone(),
+                    hex: false,
+                    out: None,
+                }),
+                handle: uudecode,
+            },
+            SelectOption {
+                message: "URL Edit",
+                arg_name: Some(Tools::URLEdit {
+                    data: tool_data.clone(),
+                    set_param: vec![],
+                    remove_param: vec![],
+                    scheme: None,
+                    host: None,
+                    path: None,
+                    join: None,
+                }),
+                handle: url_edit,
+            },
+            SelectOption {
+                message: "Query to JSON",
+                arg_name: Some(Tools::QueryToJSON {
+                    data: tool_data.clone(),
+                }),
+                handle: query_to_json,
+            },
+            SelectOption {
+                message: "JSON to Query",
+                arg_name: Some(Tools::JSONToQuery {
+                    data: tool_data.clone(),
+                    style: "brackets".to_string(),
+                    arrays: "brackets".to_string(),
+                }),
+                handle: json_to_query,
+            },
+            SelectOption {
+                message: "IDNA Encode",
+                arg_name: Some(Tools::IdnaEncode {
+                    data: tool_data.clone(),
+                }),
+                handle: idna_encode,
+            },
+            SelectOption {
+                message: "IDNA Decode",
+                arg_name: Some(Tools::IdnaDecode {
+                    data: tool_data.clone(),
+                }),
+                handle: idna_decode,
+            },
+            SelectOption {
+                message: "Escape",
+                arg_name: Some(Tools::Escape {
+                    data: tool_data.clone(),
+                    lang: "json".to_string(),
+                    ascii: false,
+                    raw: false,
+                }),
+                handle: escape,
+            },
+            SelectOption {
+                message: "Unescape",
+                arg_name: Some(Tools::Unescape {
+                    data: tool_data.clone(),
+                    lang: "json".to_string(),
+                }),
+                handle: unescape,
+            },
+            SelectOption {
+                message: "Inspect String",
+                arg_name: Some(Tools::InspectString {
+                    data: tool_data.clone(),
+                    file: None,
+                }),
+                handle: inspect_string,
+            },
+            SelectOption {
+                message: "Charset Convert",
+                arg_name: Some(Tools::CharsetConvert {
+                    data: tool_data.clone(),
+                    file: None,
+                    from: "auto".to_string(),
+                    to: "utf-8".to_string(),
+                    detect: false,
+                    fix_mojibake: false,
+                    hex: false,
+                    out: None,
+                }),
+                handle: charset_convert,
+            },
+            SelectOption {
+                message: "Unicode Normalize",
+                arg_name: Some(Tools::UnicodeNormalize {
+                    data: tool_data.clone(),
+                    form: "nfc".to_string(),
+                    case_fold: false,
+                    width: None,
+                    strip_diacritics: false,
+                    check: false,
+                }),
+                handle: unicode_normalize,
+            },
             SelectOption {
                 message: "Quit",
                 arg_name: None,
@@ -244,7 +1515,7 @@ fn main() {
     }
 
     match select.arg_match(&args.tool.as_ref()) {
-        Some(option) => (option.handle)(&select.args, &select.term),
+        Some(option) => (option.handle)(select.args, &select.term),
         None => select.numbered_list(),
     }
 }
@@ -261,9 +1532,9 @@ fn read_input(t: &Term, multi_line: Option<bool>, end_line: Option<&str>) -> Str
             }
             input.push_str(format!("{next_line}\n").as_str());
         }
-        return input;
+        input
     } else {
-        return t.read_line().unwrap();
+        t.read_line().unwrap()
     }
 }
 
@@ -2,6 +2,7 @@ use crate::{error, print, read_input};
 use console::Style;
 use console::Term;
 use std::fmt::Display;
+use std::mem::discriminant;
 
 pub struct Select<'a, T, A: PartialEq + Display> {
     pub options: Vec<SelectOption<'a, T, A>>,
@@ -40,31 +41,24 @@ impl<T, A: PartialEq + Display> Select<'_, T, A> {
             return;
         }
 
-        (self.options[selection - 1].handle)(&self.args, &self.term);
+        (self.options[selection - 1].handle)(self.args, &self.term);
     }
 
+    // Only the tool variant is compared so that flags don't have to be mirrored in the options
     pub fn arg_match(&self, arg: &Option<&A>) -> Option<&SelectOption<'_, T, A>> {
-        match arg {
-            None => return None,
-            _ => {
-                for option in self.options.iter() {
-                    if arg == &option.arg_name.as_ref() {
-                        return Some(option);
-                    }
-                }
-            }
-        };
-
-        return None;
+        let arg = (*arg)?;
+        self.options.iter().find(|option| {
+            option
+                .arg_name
+                .as_ref()
+                .is_some_and(|name| discriminant(name) == discriminant(arg))
+        })
     }
 
     pub fn list_options(&self) {
         for option in self.options.iter() {
-            if !option.arg_name.is_none() {
-                print(
-                    &self.term,
-                    option.arg_name.as_ref().unwrap().to_string().as_str(),
-                );
+            if let Some(arg_name) = &option.arg_name {
+                print(&self.term, arg_name.to_string().as_str());
             }
         }
     }
@@ -1,13 +1,74 @@
 use crate::{Args, Tools, error, print, read_input, success};
 use base64::{Engine as _, engine::general_purpose};
 use console::Term;
-use htmlescape;
-use serde_json;
-use serde_yaml_ng;
-use std::str::FromStr;
-use toml;
-use url::Url;
-use urlencoding::{decode, encode};
+use formats::{Format, parse_any, parse_as, render};
+use ndjson::{collect_lines, process_lines, split_lines};
+use serde::Deserialize;
+
+mod base32;
+mod base58;
+mod base85;
+mod binary;
+mod bson;
+mod cbor;
+mod charset;
+mod confusables;
+mod datauri;
+mod diff;
+mod env;
+mod escape;
+mod flatten;
+mod formats;
+mod hex;
+mod html;
+mod idn;
+mod infer;
+mod ini;
+mod inspect;
+mod jq;
+mod mail;
+mod msgpack;
+mod ndjson;
+mod normalize;
+mod patch;
+mod percent;
+mod properties;
+mod protobuf;
+mod query;
+mod querystring;
+mod schema;
+mod types;
+mod urls;
+mod uu;
+pub use base32::{base_32_decode, base_32_encode};
+pub use base58::{base_58_decode, base_58_encode};
+pub use base85::{base_85_decode, base_85_encode};
+pub use bson::{bson_to_json, json_to_bson};
+pub use cbor::{cbor_to_json, json_to_cbor};
+pub use charset::charset_convert;
+pub use datauri::{data_uri_decode, data_uri_encode};
+pub use diff::diff;
+pub use escape::{escape, unescape};
+pub use flatten::{json_flatten, json_unflatten};
+pub use hex::{hex_decode, hex_encode, hexdump};
+pub use html::{html_escape, html_unescape};
+pub use idn::{idna_decode, idna_encode};
+pub use infer::json_schema_infer;
+pub use inspect::inspect_string;
+pub use mail::{
+    encoded_word_decode, encoded_word_encode, quoted_printable_decode, quoted_printable_encode,
+};
+pub use msgpack::{json_to_msgpack, msgpack_to_json};
+pub use normalize::unicode_normalize;
+pub use patch::{json_merge_patch, json_patch};
+pub use percent::{url_decode, url_encode};
+pub use protobuf::protobuf_decode;
+pub use query::json_query;
+pub use querystring::{json_to_query, query_to_json};
+pub use schema::json_validate;
+pub use types::json_to_types;
+pub use urls::{url_edit, url_parse};
+pub use uu::{uudecode, uuencode};
 
 pub fn base_64_decode(a: &Args, t: &Term) {
     let msg = "Enter a Base64 encoded string to decode:";
@@ -29,113 +90,52 @@ pub fn base_64_decode(a: &Args, t: &Term) {
 }
 
 fn _base_64_decode(input: &str) -> Result<String, String> {
-    let result = general_purpose::STANDARD_NO_PAD.decode(input);
-    match result {
-        Ok(result_bytes) => match String::from_utf8(result_bytes) {
-            Ok(result_str) => return Ok(result_str),
-            Err(err) => return Err(format!("Error converting bytes to string: {err}")),
-        },
-        Err(err) => return Err(format!("Error decoding Base64 string: {err}")),
+    let result_bytes = general_purpose::STANDARD_NO_PAD
+        .decode(input)
+        .map_err(|err| format!("Error decoding Base64 string: {err}"))?;
+    match String::from_utf8(result_bytes) {
+        Ok(result_str) => Ok(result_str),
+        Err(err) => Err(format!(
+            "The decoded data isn't UTF-8 text. {}",
+            inspect::explain_invalid_utf8(err.as_bytes())
+        )),
     }
 }
 
+// Used for binary input, where padding and the URL safe alphabet are both accepted since either
+// shows up in logs and tokens
+fn _base_64_decode_bytes(input: &str) -> Result<Vec<u8>, String> {
+    let input: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    general_purpose::STANDARD_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .map_err(|err| format!("Error decoding Base64 string: {err}"))
+}
+
 pub fn base_64_encode(a: &Args, t: &Term) {
     let msg = "Enter a string to Base64 encode:";
-    let input = match &a.tool {
-        Some(Tools::Base64Encode { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
+    let data = match &a.tool {
+        Some(Tools::Base64Encode { data }) => data.clone(),
+        _ => None,
     };
+    let input = _text_input(t, msg, data);
 
     let result = general_purpose::STANDARD_NO_PAD.encode(input);
     success(t, result.as_str(), None, a.plain);
 }
 
-pub fn url_decode(a: &Args, t: &Term) {
-    let msg = "Enter a URL encoded string to decode:";
-    let input = match &a.tool {
-        Some(Tools::URLDecode { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
-    };
-
-    let result = decode(input.as_str());
-    match result {
-        Ok(result_str) => success(t, result_str.to_string().as_str(), None, a.plain),
-        Err(err) => error(
-            t,
-            err.to_string().as_str(),
-            Some("Error decoding URL encoded string"),
-        ),
-    }
-}
-
-pub fn url_encode(a: &Args, t: &Term) {
-    let msg = "Enter a string to URL encode:";
-    let input = match &a.tool {
-        Some(Tools::URLEncode { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
-    };
-
-    let result = encode(input.as_str());
-    success(t, result.to_string().as_str(), None, a.plain);
-}
-
-pub fn html_unescape(a: &Args, t: &Term) {
-    let msg = "Enter a HTML escaped string to unescape:";
-    let input = match &a.tool {
-        Some(Tools::HTMLUnescape { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
-    };
-
-    let result = htmlescape::decode_html(input.as_str());
-    match result {
-        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
-        Err(err) => error(
-            t,
-            format!("{err:?}").as_str(),
-            Some("Error decoding HTML escaped string"),
-        ),
-    }
-}
-
-pub fn html_escape(a: &Args, t: &Term) {
-    let msg = "Enter a string to HTML escape:";
-    let input = match &a.tool {
-        Some(Tools::HTMLEscape { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
-    };
-
-    let result = htmlescape::encode_attribute(input.as_str());
-    success(t, result.as_str(), None, a.plain);
+fn _text_input(t: &Term, msg: &str, data: Option<String>) -> String {
+    data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, None, None)
+    })
 }
 
 pub fn jwt_decode(a: &Args, t: &Term) {
@@ -183,7 +183,7 @@ pub fn jwt_decode(a: &Args, t: &Term) {
     }
 
     if header {
-        let header = _base_64_decode(&mut String::from_str(jwt_parts[0]).unwrap());
+        let header = _base_64_decode(jwt_parts[0]);
         match header {
             Ok(header_str) => success(t, header_str.as_str(), Some("Header Data"), a.plain),
             Err(err) => error(t, err.as_str(), Some("Error decoding header")),
@@ -191,7 +191,7 @@ pub fn jwt_decode(a: &Args, t: &Term) {
     }
 
     if payload {
-        let payload = _base_64_decode(&mut String::from_str(jwt_parts[1]).unwrap());
+        let payload = _base_64_decode(jwt_parts[1]);
         match payload {
             Ok(payload_str) => success(t, payload_str.as_str(), Some("Payload Data"), a.plain),
             Err(err) => error(t, err.as_str(), Some("Error decoding payload")),
@@ -199,76 +199,10 @@ pub fn jwt_decode(a: &Args, t: &Term) {
     }
 }
 
-pub fn url_parse(a: &Args, t: &Term) {
-    let msg = "Enter a URL to parse:";
-    let input = match &a.tool {
-        Some(Tools::URLParse { data }) => data.clone().unwrap_or_else(|| {
-            print(t, msg);
-            read_input(t, None, None)
-        }),
-        _ => {
-            print(t, msg);
-            read_input(t, None, None)
-        }
-    };
-    match Url::parse(&input) {
-        Ok(url) => {
-            success(
-                t,
-                format!("{}", url.scheme()).as_str(),
-                Some("Scheme"),
-                false,
-            );
-            success(
-                t,
-                format!("{}", url.username()).as_str(),
-                Some("Username"),
-                false,
-            );
-            success(
-                t,
-                format!("{}", url.password().unwrap_or("")).as_str(),
-                Some("Password"),
-                false,
-            );
-            success(
-                t,
-                format!("{}", url.host_str().unwrap_or("")).as_str(),
-                Some("Host"),
-                false,
-            );
-            success(
-                t,
-                format!(
-                    "{}",
-                    url.port().map_or("".to_string(), |port| port.to_string())
-                )
-                .as_str(),
-                Some("Port"),
-                false,
-            );
-            success(t, format!("{}", url.path()).as_str(), Some("Path"), false);
-            success(
-                t,
-                format!("{}", url.query().unwrap_or("")).as_str(),
-                Some("Query"),
-                false,
-            );
-            success(
-                t,
-                format!("{}", url.fragment().unwrap_or("")).as_str(),
-                Some("Fragment"),
-                false,
-            );
-        }
-        Err(err) => error(t, err.to_string().as_str(), None),
-    }
-}
-
 pub fn format_json(a: &Args, t: &Term) {
     let msg = "Enter some JSON to format (type 'END' on a new line to stop input):";
     let input = match &a.tool {
-        Some(Tools::FormatJSON { data }) => data.clone().unwrap_or_else(|| {
+        Some(Tools::FormatJSON { data, .. }) => data.clone().unwrap_or_else(|| {
             print(t, msg);
             read_input(t, Some(true), Some("END"))
         }),
@@ -277,6 +211,38 @@ pub fn format_json(a: &Args, t: &Term) {
             read_input(t, Some(true), Some("END"))
         }
     };
+    let (ndjson, minify, slurp, split) = match &a.tool {
+        Some(Tools::FormatJSON {
+            data: _,
+            ndjson,
+            minify,
+            slurp,
+            split,
+        }) => (*ndjson, *minify, *slurp, *split),
+        _ => (false, false, false, false),
+    };
+    let to_string = |value: &serde_json::Value| {
+        if minify {
+            serde_json::to_string(value).map_err(|err| err.to_string())
+        } else {
+            serde_json::to_string_pretty(value).map_err(|err| err.to_string())
+        }
+    };
+
+    if slurp {
+        match to_string(&collect_lines(t, &input)) {
+            Ok(json_str) => success(t, json_str.as_str(), None, true),
+            Err(err) => error(t, err.as_str(), None),
+        }
+        return;
+    }
+    if ndjson {
+        process_lines(t, &input, |value| {
+            to_string(&value).map(|json_str| vec![json_str])
+        });
+        return;
+    }
+
     let result: serde_json::Value = match serde_json::from_str(input.as_str()) {
         Ok(val) => val,
         Err(err) => {
@@ -284,9 +250,18 @@ pub fn format_json(a: &Args, t: &Term) {
             return;
         }
     };
-    match serde_json::to_string_pretty(&result) {
+    if split {
+        match split_lines(&result) {
+            Ok(lines) => lines
+                .iter()
+                .for_each(|line| success(t, line.as_str(), None, true)),
+            Err(err) => error(t, err.as_str(), None),
+        }
+        return;
+    }
+    match to_string(&result) {
         Ok(json_str) => success(t, json_str.as_str(), None, true),
-        Err(err) => error(t, err.to_string().as_str(), None),
+        Err(err) => error(t, err.as_str(), None),
     }
 }
 
@@ -318,7 +293,7 @@ pub fn format_yaml(a: &Args, t: &Term) {
 pub fn yaml_to_json(a: &Args, t: &Term) {
     let msg = "Enter some YAML to convert to JSON (type 'END' on a new line to stop input):";
     let input = match &a.tool {
-        Some(Tools::YamlToJSON { data }) => data.clone().unwrap_or_else(|| {
+        Some(Tools::YamlToJSON { data, .. }) => data.clone().unwrap_or_else(|| {
             print(t, msg);
             read_input(t, Some(true), Some("END"))
         }),
@@ -327,6 +302,26 @@ pub fn yaml_to_json(a: &Args, t: &Term) {
             read_input(t, Some(true), Some("END"))
         }
     };
+    let ndjson = match &a.tool {
+        Some(Tools::YamlToJSON { data: _, ndjson }) => *ndjson,
+        _ => false,
+    };
+
+    if ndjson {
+        for (index, document) in serde_yaml_ng::Deserializer::from_str(input.as_str()).enumerate() {
+            let label = format!("Document {}", index + 1);
+            match serde_json::Value::deserialize(document) {
+                Ok(val) => success(t, val.to_string().as_str(), None, true),
+                Err(err) => {
+                    // The YAML stream can't be resumed after a syntax error
+                    error(t, err.to_string().as_str(), Some(label.as_str()));
+                    break;
+                }
+            }
+        }
+        return;
+    }
+
     let result: serde_yaml_ng::Value = match serde_yaml_ng::from_str(input.as_str()) {
         Ok(val) => val,
         Err(err) => {
@@ -364,3 +359,122 @@ pub fn toml_to_json(a: &Args, t: &Term) {
         Err(err) => error(t, err.to_string().as_str(), None),
     }
 }
+
+pub fn env_to_json(a: &Args, t: &Term) {
+    let msg = "Enter a .env file to convert to JSON (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::EnvToJSON { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, Some(Format::Env), Format::Json) {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn ini_to_json(a: &Args, t: &Term) {
+    let msg = "Enter some INI to convert to JSON (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::IniToJSON { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, Some(Format::Ini), Format::Json) {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn properties_to_json(a: &Args, t: &Term) {
+    let msg =
+        "Enter some Java properties to convert to JSON (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::PropertiesToJSON { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, Some(Format::Properties), Format::Json) {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_env(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to .env (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::JSONToEnv { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, None, Format::Env) {
+        Ok(env_str) => success(t, env_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_ini(a: &Args, t: &Term) {
+    let msg =
+        "Enter some JSON, YAML or TOML to convert to INI (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::JSONToIni { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, None, Format::Ini) {
+        Ok(ini_str) => success(t, ini_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_properties(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to Java properties (type 'END' on a new line to stop input):";
+    let input = match &a.tool {
+        Some(Tools::JSONToProperties { data }) => data.clone().unwrap_or_else(|| {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }),
+        _ => {
+            print(t, msg);
+            read_input(t, Some(true), Some("END"))
+        }
+    };
+    match _convert(&input, None, Format::Properties) {
+        Ok(properties_str) => success(t, properties_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+// The input format is detected when `from` isn't given
+fn _convert(input: &str, from: Option<Format>, to: Format) -> Result<String, String> {
+    let value = match from {
+        Some(format) => parse_as(input, format)?,
+        None => parse_any(input)?.0,
+    };
+    render(&value, to)
+}
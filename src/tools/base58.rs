@@ -0,0 +1,139 @@
+use super::_text_input;
+use super::binary::{read_raw, write_decoded};
+use crate::{Args, Tools, error, success};
+use console::Term;
+use sha2::{Digest, Sha256};
+
+pub fn base_58_encode(a: &Args, t: &Term) {
+    let msg = "Enter a string to Base58 encode:";
+    let (data, file, alphabet, check) = match &a.tool {
+        Some(Tools::Base58Encode {
+            data,
+            file,
+            alphabet,
+            check,
+        }) => (data.clone(), file.clone(), alphabet.clone(), *check),
+        _ => (None, None, "bitcoin".to_string(), false),
+    };
+    match read_raw(t, msg, data, file) {
+        Ok(mut bytes) => {
+            if check {
+                bytes.extend(checksum(&bytes));
+            }
+            success(t, encode(&bytes, &alphabet).as_str(), None, a.plain);
+        }
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn base_58_decode(a: &Args, t: &Term) {
+    let msg = "Enter a Base58 encoded string to decode:";
+    let (data, alphabet, check, hex, out) = match &a.tool {
+        Some(Tools::Base58Decode {
+            data,
+            alphabet,
+            check,
+            hex,
+            out,
+        }) => (data.clone(), alphabet.clone(), *check, *hex, out.clone()),
+        _ => (None, "bitcoin".to_string(), false, false, None),
+    };
+    let input = _text_input(t, msg, data);
+    let result = _base_58_decode(&input, &alphabet, check)
+        .and_then(|bytes| write_decoded(t, a.plain, bytes, hex, out));
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+fn symbols(alphabet: &str) -> &'static [u8; 58] {
+    match alphabet {
+        "flickr" => b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ",
+        "ripple" => b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz",
+        _ => b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+    }
+}
+
+// Base58Check appends the first four bytes of a double SHA-256 of the payload
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Every leading zero byte becomes a leading '1' (the first symbol), the rest is converted as one
+// big number
+fn encode(bytes: &[u8], alphabet: &str) -> String {
+    let symbols = symbols(alphabet);
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    let mut digits: Vec<u8> = vec![];
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut encoded = String::new();
+    encoded.extend(std::iter::repeat_n(symbols[0] as char, zeros));
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|digit| symbols[*digit as usize] as char),
+    );
+    encoded
+}
+
+fn _base_58_decode(input: &str, alphabet: &str, check: bool) -> Result<Vec<u8>, String> {
+    let symbols = symbols(alphabet);
+    let input = input.trim();
+    let zeros = input
+        .chars()
+        .take_while(|c| *c == symbols[0] as char)
+        .count();
+    let mut bytes: Vec<u8> = vec![];
+    for (i, c) in input.chars().enumerate().skip(zeros) {
+        let value = symbols
+            .iter()
+            .position(|symbol| *symbol as char == c)
+            .ok_or(format!("Invalid Base58 character '{c}' at position {i}"))?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+
+    if check {
+        if bytes.len() < 4 {
+            return Err("Too short to contain a Base58Check checksum".to_string());
+        }
+        let payload = bytes[..bytes.len() - 4].to_vec();
+        let expected = checksum(&payload);
+        if bytes[bytes.len() - 4..] != expected {
+            return Err(format!(
+                "Checksum mismatch: expected {} but found {}",
+                hex(&expected),
+                hex(&bytes[bytes.len() - 4..])
+            ));
+        }
+        return Ok(payload);
+    }
+    Ok(bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
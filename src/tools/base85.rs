@@ -0,0 +1,162 @@
+use super::_text_input;
+use super::binary::{read_raw, write_decoded};
+use crate::{Args, Tools, error, success};
+use console::Term;
+
+const Z85: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+pub fn base_85_encode(a: &Args, t: &Term) {
+    let msg = "Enter a string to Base85 encode:";
+    let (data, file, variant, delimiters) = match &a.tool {
+        Some(Tools::Base85Encode {
+            data,
+            file,
+            variant,
+            delimiters,
+        }) => (data.clone(), file.clone(), variant.clone(), *delimiters),
+        _ => (None, None, "ascii85".to_string(), false),
+    };
+    let result = read_raw(t, msg, data, file).and_then(|bytes| match variant.as_str() {
+        "z85" => encode_z85(&bytes),
+        _ if delimiters => Ok(format!("<~{}~>", encode_ascii85(&bytes))),
+        _ => Ok(encode_ascii85(&bytes)),
+    });
+    match result {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn base_85_decode(a: &Args, t: &Term) {
+    let msg = "Enter a Base85 encoded string to decode:";
+    let (data, variant, hex, out) = match &a.tool {
+        Some(Tools::Base85Decode {
+            data,
+            variant,
+            hex,
+            out,
+        }) => (data.clone(), variant.clone(), *hex, out.clone()),
+        _ => (None, "ascii85".to_string(), false, None),
+    };
+    let input = _text_input(t, msg, data);
+    let result = match variant.as_str() {
+        "z85" => decode_z85(&input),
+        _ => decode_ascii85(&input),
+    };
+    if let Err(err) = result.and_then(|bytes| write_decoded(t, a.plain, bytes, hex, out)) {
+        error(t, err.as_str(), None);
+    }
+}
+
+fn digits(mut value: u32) -> [u8; 5] {
+    let mut digits = [0; 5];
+    for digit in digits.iter_mut().rev() {
+        *digit = (value % 85) as u8;
+        value /= 85;
+    }
+    digits
+}
+
+// A group of four zero bytes is shortened to 'z' and a final partial group of n bytes is written
+// as n + 1 characters
+fn encode_ascii85(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let value = u32::from_be_bytes(group);
+        if value == 0 && chunk.len() == 4 {
+            encoded.push('z');
+            continue;
+        }
+        encoded.extend(
+            digits(value)[..chunk.len() + 1]
+                .iter()
+                .map(|digit| (digit + b'!') as char),
+        );
+    }
+    encoded
+}
+
+fn decode_ascii85(input: &str) -> Result<Vec<u8>, String> {
+    let input = input.trim();
+    let input = input.strip_prefix("<~").unwrap_or(input);
+    let input = input.strip_suffix("~>").unwrap_or(input);
+    let mut bytes = vec![];
+    let mut group = vec![];
+    for (i, c) in input.chars().filter(|c| !c.is_whitespace()).enumerate() {
+        match c {
+            'z' if group.is_empty() => bytes.extend([0; 4]),
+            '!'..='u' => group.push(c as u8 - b'!'),
+            _ => return Err(format!("Invalid Ascii85 character '{c}' at position {i}")),
+        }
+        if group.len() == 5 {
+            bytes.extend(group_value(&group)?.to_be_bytes());
+            group.clear();
+        }
+    }
+    match group.len() {
+        0 => {}
+        1 => {
+            return Err(
+                "Invalid Ascii85 length, the last group has a single character".to_string(),
+            );
+        }
+        // The partial group is padded with the highest digit so it rounds down to the right bytes
+        n => {
+            group.resize(5, 84);
+            bytes.extend(&group_value(&group)?.to_be_bytes()[..n - 1]);
+        }
+    }
+    Ok(bytes)
+}
+
+fn group_value(group: &[u8]) -> Result<u32, String> {
+    let value = group
+        .iter()
+        .fold(0u64, |value, digit| value * 85 + *digit as u64);
+    u32::try_from(value).map_err(|_| "Invalid Base85 group, its value is above 2^32".to_string())
+}
+
+fn encode_z85(bytes: &[u8]) -> Result<String, String> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(format!(
+            "Z85 can only encode a multiple of 4 bytes, got {}",
+            bytes.len()
+        ));
+    }
+    Ok(bytes
+        .chunks(4)
+        .flat_map(|chunk| digits(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])))
+        .map(|digit| Z85[digit as usize] as char)
+        .collect())
+}
+
+fn decode_z85(input: &str) -> Result<Vec<u8>, String> {
+    let input: Vec<char> = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if !input.len().is_multiple_of(5) {
+        return Err(format!(
+            "Z85 input must be a multiple of 5 characters, got {}",
+            input.len()
+        ));
+    }
+    let mut bytes = vec![];
+    for (index, chunk) in input.chunks(5).enumerate() {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                Z85.iter()
+                    .position(|symbol| *symbol as char == *c)
+                    .map(|digit| digit as u8)
+                    .ok_or(format!(
+                        "Invalid Z85 character '{c}' at position {}",
+                        index * 5 + i
+                    ))
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        bytes.extend(group_value(&group)?.to_be_bytes());
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,235 @@
+use super::{_base_64_decode_bytes, _text_input};
+use crate::success;
+use base64::{Engine as _, engine::general_purpose};
+use console::Term;
+use serde_json::{Map, Value, json};
+use std::fs;
+
+// Raw bytes come from a file, otherwise the text is decoded as hex or Base64
+pub fn read_bytes(
+    t: &Term,
+    msg: &str,
+    data: Option<String>,
+    file: Option<String>,
+    encoding: &str,
+) -> Result<Vec<u8>, String> {
+    if let Some(path) = file {
+        return fs::read(&path).map_err(|err| format!("Error reading {path}: {err}"));
+    }
+    let input = _text_input(t, msg, data);
+    match encoding {
+        "hex" => decode_hex(&input),
+        "base64" => _base_64_decode_bytes(&input),
+        _ if is_hex(&input) => decode_hex(&input),
+        _ => _base_64_decode_bytes(&input),
+    }
+}
+
+// Like `read_bytes` but text input is taken as is
+pub fn read_raw(
+    t: &Term,
+    msg: &str,
+    data: Option<String>,
+    file: Option<String>,
+) -> Result<Vec<u8>, String> {
+    match file {
+        Some(path) => fs::read(&path).map_err(|err| format!("Error reading {path}: {err}")),
+        None => Ok(_text_input(t, msg, data).into_bytes()),
+    }
+}
+
+pub fn write_bytes(
+    t: &Term,
+    bytes: &[u8],
+    encoding: &str,
+    out: Option<String>,
+) -> Result<(), String> {
+    match out {
+        Some(path) => {
+            fs::write(&path, bytes).map_err(|err| format!("Error writing {path}: {err}"))?;
+            success(
+                t,
+                format!("Wrote {} bytes to {path}", bytes.len()).as_str(),
+                None,
+                false,
+            );
+        }
+        None => success(t, encode_bytes(bytes, encoding).as_str(), None, true),
+    }
+    Ok(())
+}
+
+// Decoded bytes are printed as text when they're valid UTF-8
+pub fn write_decoded(
+    t: &Term,
+    plain: bool,
+    bytes: Vec<u8>,
+    hex: bool,
+    out: Option<String>,
+) -> Result<(), String> {
+    if out.is_some() {
+        return write_bytes(t, &bytes, "hex", out);
+    }
+    let result = if hex {
+        encode_bytes(&bytes, "hex")
+    } else {
+        String::from_utf8(bytes).map_err(|err| {
+            format!(
+                "Error converting bytes to string: {err}. Use --hex to print them as hex or --out to write them to a file"
+            )
+        })?
+    };
+    success(t, result.as_str(), None, plain);
+    Ok(())
+}
+
+pub fn encode_bytes(bytes: &[u8], encoding: &str) -> String {
+    match encoding {
+        "hex" => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+        _ => general_purpose::STANDARD.encode(bytes),
+    }
+}
+
+// Tolerates `0x` and `\x` prefixes and bytes split up by spaces, colons, commas or dashes
+fn hex_digits(input: &str) -> String {
+    input
+        .replace("\\x", " ")
+        .split(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-'))
+        .map(|part| {
+            part.strip_prefix("0x")
+                .or(part.strip_prefix("0X"))
+                .unwrap_or(part)
+        })
+        .collect()
+}
+
+// Anything that could be either, like `deadbeef`, is treated as hex
+fn is_hex(input: &str) -> bool {
+    let digits = hex_digits(input);
+    !digits.is_empty()
+        && digits.len().is_multiple_of(2)
+        && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits = hex_digits(input);
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex digit '{c}'"));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex input must have an even number of digits".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| {
+                format!(
+                    "Invalid hex byte '{}' at offset {}",
+                    &digits[i..i + 2],
+                    i / 2
+                )
+            })
+        })
+        .collect()
+}
+
+// Values with no JSON equivalent are wrapped in single `$` keyed objects. Binary data is written
+// as MongoDB extended JSON, so every format reads it back, BSON included
+pub fn binary(bytes: &[u8]) -> Value {
+    json!({ "$binary": { "base64": general_purpose::STANDARD.encode(bytes), "subType": "00" } })
+}
+
+pub fn float(f: f64) -> Value {
+    match serde_json::Number::from_f64(f) {
+        Some(number) => Value::Number(number),
+        None => json!({ "$float": f.to_string() }),
+    }
+}
+
+// The shorthand `{"$binary": "AQI="}` is accepted too
+pub fn as_binary(object: &Map<String, Value>) -> Option<Result<Vec<u8>, String>> {
+    match (object.len(), object.get("$binary")) {
+        (1, Some(Value::String(data))) => Some(_base_64_decode_bytes(data)),
+        (1, Some(Value::Object(binary))) => match binary.get("base64") {
+            Some(Value::String(data)) => Some(_base_64_decode_bytes(data)),
+            _ => Some(Err("$binary needs a base64 string".to_string())),
+        },
+        _ => None,
+    }
+}
+
+// Expands the `$binary` shorthand into the extended JSON form the bson crate expects
+pub fn extended_binary(value: Value) -> Value {
+    match value {
+        Value::Object(object) => match (object.len(), object.get("$binary")) {
+            (1, Some(Value::String(data))) => {
+                json!({ "$binary": { "base64": data, "subType": "00" } })
+            }
+            _ => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, extended_binary(value)))
+                    .collect(),
+            ),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(extended_binary).collect()),
+        other => other,
+    }
+}
+
+pub fn as_float(object: &Map<String, Value>) -> Option<Result<f64, String>> {
+    match (object.len(), object.get("$float")) {
+        (1, Some(Value::String(f))) => Some(
+            f.parse::<f64>()
+                .map_err(|_| format!("Invalid $float value '{f}'")),
+        ),
+        _ => None,
+    }
+}
+
+// Map keys that aren't strings are written as their compact JSON form
+pub fn key_string(key: Value) -> String {
+    match key {
+        Value::String(key) => key,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{as_binary, binary, decode_hex, extended_binary, is_hex};
+    use serde_json::json;
+
+    #[test]
+    fn hex_input() {
+        assert_eq!(decode_hex("de ad:BE-ef").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode_hex("0x01 0x02").unwrap(), [1, 2]);
+        assert_eq!(decode_hex("\\x41\\x42").unwrap(), b"AB");
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("aéb").is_err());
+        assert!(decode_hex("zz").is_err());
+        assert!(!is_hex("aé"));
+    }
+
+    #[test]
+    fn binary_markers() {
+        let value = binary(&[1, 2]);
+        assert_eq!(
+            value,
+            json!({"$binary": {"base64": "AQI=", "subType": "00"}})
+        );
+        assert_eq!(
+            as_binary(value.as_object().unwrap()).unwrap().unwrap(),
+            [1, 2]
+        );
+        let shorthand = json!({"$binary": "AQI"});
+        assert_eq!(
+            as_binary(shorthand.as_object().unwrap()).unwrap().unwrap(),
+            [1, 2]
+        );
+        assert_eq!(
+            extended_binary(json!([{"$binary": "AQI="}])),
+            json!([value])
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use super::binary::{extended_binary, read_bytes, write_bytes};
+use super::formats::parse_any;
+use crate::{Args, Tools, error, print, read_input, success};
+use ::bson::{Bson, Document};
+use console::Term;
+use serde_json::{Map, Value};
+
+// Types JSON can't express are written as MongoDB extended JSON, e.g. `{"$oid": "..."}`
+pub fn bson_to_json(a: &Args, t: &Term) {
+    let msg = "Enter BSON data as hex or Base64 to convert to JSON:";
+    let (data, file, encoding, canonical) = match &a.tool {
+        Some(Tools::BSONToJSON {
+            data,
+            file,
+            encoding,
+            canonical,
+        }) => (data.clone(), file.clone(), encoding.clone(), *canonical),
+        _ => (None, None, "auto".to_string(), false),
+    };
+    let result = read_bytes(t, msg, data, file, &encoding).and_then(|bytes| {
+        let document = decode(&bytes)?;
+        let value = if canonical {
+            Bson::Document(document).into_canonical_extjson()
+        } else {
+            Bson::Document(document).into_relaxed_extjson()
+        };
+        serde_json::to_string_pretty(&value).map_err(|err| err.to_string())
+    });
+    match result {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<Document, String> {
+    let mut reader = bytes;
+    let document =
+        Document::from_reader(&mut reader).map_err(|err| format!("Error decoding BSON: {err}"))?;
+    if !reader.is_empty() {
+        return Err(format!(
+            "{} unexpected bytes after the BSON document",
+            reader.len()
+        ));
+    }
+    Ok(document)
+}
+
+pub fn json_to_bson(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to BSON (type 'END' on a new line to stop input):";
+    let (data, encoding, out) = match &a.tool {
+        Some(Tools::JSONToBSON {
+            data,
+            encoding,
+            out,
+        }) => (data.clone(), encoding.clone(), out.clone()),
+        _ => (None, "base64".to_string(), None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    let result = parse_any(&input).and_then(|(value, _)| {
+        let object: Map<String, Value> = match value {
+            Value::Object(object) => object
+                .into_iter()
+                .map(|(key, value)| (key, extended_binary(value)))
+                .collect(),
+            _ => return Err("Only an object can be converted to BSON".to_string()),
+        };
+        let bytes = Document::try_from(object)
+            .and_then(|document| document.to_vec())
+            .map_err(|err| format!("Error encoding BSON: {err}"))?;
+        write_bytes(t, &bytes, &encoding, out)
+    });
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use ::bson::doc;
+
+    #[test]
+    fn trailing_bytes() {
+        let mut bytes = doc! {"a": 1}.to_vec().unwrap();
+        assert_eq!(decode(&bytes).unwrap(), doc! {"a": 1});
+        bytes.extend([0, 0]);
+        assert_eq!(
+            decode(&bytes).unwrap_err(),
+            "2 unexpected bytes after the BSON document"
+        );
+        assert!(decode(&bytes[..4]).is_err());
+    }
+}
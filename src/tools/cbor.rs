@@ -0,0 +1,170 @@
+use super::binary::{as_binary, as_float, binary, float, key_string, read_bytes, write_bytes};
+use super::formats::parse_any;
+use crate::{Args, Tools, error, print, read_input, success};
+use ciborium::Value as Cbor;
+use console::Term;
+use serde_json::{Map, Value, json};
+
+pub fn cbor_to_json(a: &Args, t: &Term) {
+    let msg = "Enter CBOR data as hex or Base64 to convert to JSON:";
+    let (data, file, encoding) = match &a.tool {
+        Some(Tools::CborToJSON {
+            data,
+            file,
+            encoding,
+        }) => (data.clone(), file.clone(), encoding.clone()),
+        _ => (None, None, "auto".to_string()),
+    };
+    let result = read_bytes(t, msg, data, file, &encoding).and_then(|bytes| {
+        let mut reader = bytes.as_slice();
+        let value: Cbor = ciborium::from_reader(&mut reader)
+            .map_err(|err| format!("Error decoding CBOR: {err}"))?;
+        if !reader.is_empty() {
+            return Err(format!(
+                "{} unexpected bytes after the CBOR value",
+                reader.len()
+            ));
+        }
+        serde_json::to_string_pretty(&to_json(value)?).map_err(|err| err.to_string())
+    });
+    match result {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_cbor(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to CBOR (type 'END' on a new line to stop input):";
+    let (data, encoding, out) = match &a.tool {
+        Some(Tools::JSONToCbor {
+            data,
+            encoding,
+            out,
+        }) => (data.clone(), encoding.clone(), out.clone()),
+        _ => (None, "base64".to_string(), None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    let result = parse_any(&input).and_then(|(value, _)| {
+        let mut bytes = vec![];
+        ciborium::into_writer(&from_json(&value)?, &mut bytes)
+            .map_err(|err| format!("Error encoding CBOR: {err}"))?;
+        write_bytes(t, &bytes, &encoding, out)
+    });
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+fn to_json(value: Cbor) -> Result<Value, String> {
+    Ok(match value {
+        Cbor::Null => Value::Null,
+        Cbor::Bool(b) => Value::Bool(b),
+        // CBOR integers reach down to -2^64, past what a JSON number here can hold
+        Cbor::Integer(i) => {
+            let i = i128::from(i);
+            match (i64::try_from(i), u64::try_from(i)) {
+                (Ok(i), _) => json!(i),
+                (_, Ok(u)) => json!(u),
+                _ => json!({ "$integer": i.to_string() }),
+            }
+        }
+        Cbor::Float(f) => float(f),
+        Cbor::Text(s) => Value::String(s),
+        Cbor::Bytes(bytes) => binary(&bytes),
+        Cbor::Tag(tag, value) => json!({ "$tag": tag, "value": to_json(*value)? }),
+        Cbor::Array(items) => {
+            Value::Array(items.into_iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Cbor::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((key_string(to_json(key)?), to_json(value)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => return Err(format!("Unsupported CBOR value {other:?}")),
+    })
+}
+
+fn from_json(value: &Value) -> Result<Cbor, String> {
+    Ok(match value {
+        Value::Null => Cbor::Null,
+        Value::Bool(b) => Cbor::Bool(*b),
+        Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+            (Some(u), _, _) => Cbor::Integer(u.into()),
+            (_, Some(i), _) => Cbor::Integer(i.into()),
+            (_, _, Some(f)) => Cbor::Float(f),
+            _ => return Err(format!("Unsupported number {n}")),
+        },
+        Value::String(s) => Cbor::Text(s.clone()),
+        Value::Array(items) => Cbor::Array(items.iter().map(from_json).collect::<Result<_, _>>()?),
+        Value::Object(object) => {
+            if let Some(bytes) = as_binary(object) {
+                return Ok(Cbor::Bytes(bytes?));
+            }
+            if let Some(f) = as_float(object) {
+                return Ok(Cbor::Float(f?));
+            }
+            if let Some(i) = as_integer(object) {
+                return Ok(Cbor::Integer(i?));
+            }
+            if let (2, Some(tag), Some(value)) =
+                (object.len(), object.get("$tag"), object.get("value"))
+            {
+                let tag = tag
+                    .as_u64()
+                    .ok_or(format!("$tag must be a positive integer, got {tag}"))?;
+                return Ok(Cbor::Tag(tag, Box::new(from_json(value)?)));
+            }
+            Cbor::Map(
+                object
+                    .iter()
+                    .map(|(key, value)| Ok((Cbor::Text(key.clone()), from_json(value)?)))
+                    .collect::<Result<_, String>>()?,
+            )
+        }
+    })
+}
+
+fn as_integer(object: &Map<String, Value>) -> Option<Result<ciborium::value::Integer, String>> {
+    match (object.len(), object.get("$integer")) {
+        (1, Some(Value::String(i))) => Some(
+            i.parse::<i128>()
+                .ok()
+                .and_then(|i| ciborium::value::Integer::try_from(i).ok())
+                .ok_or(format!("Invalid $integer value '{i}'")),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cbor, from_json, to_json};
+    use serde_json::json;
+
+    #[test]
+    fn round_trip() {
+        let value = json!({
+            "int": -5,
+            "huge": {"$integer": "-18446744073709551616"},
+            "float": 1.5,
+            "text": "café",
+            "list": [null, true, {"nested": []}],
+            "bytes": {"$binary": {"base64": "AAEC", "subType": "00"}},
+            "tagged": {"$tag": 1, "value": 1700000000},
+        });
+        let mut bytes = vec![];
+        ciborium::into_writer(&from_json(&value).unwrap(), &mut bytes).unwrap();
+        let decoded: Cbor = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(to_json(decoded).unwrap(), value);
+    }
+
+    #[test]
+    fn shorthand_binary() {
+        let value = json!({"$binary": "AQI="});
+        assert_eq!(from_json(&value).unwrap(), Cbor::Bytes(vec![1, 2]));
+    }
+}
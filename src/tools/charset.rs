@@ -0,0 +1,287 @@
+use super::binary::{read_raw, write_decoded};
+use crate::{Args, Tools, error, success};
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use console::Term;
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1250, WINDOWS_1251, WINDOWS_1252};
+
+pub fn charset_convert(a: &Args, t: &Term) {
+    let msg = "Enter a string to convert:";
+    let (data, file, from, to, detect, fix_mojibake, hex, out) = match &a.tool {
+        Some(Tools::CharsetConvert {
+            data,
+            file,
+            from,
+            to,
+            detect,
+            fix_mojibake,
+            hex,
+            out,
+        }) => (
+            data.clone(),
+            file.clone(),
+            from.clone(),
+            to.clone(),
+            *detect,
+            *fix_mojibake,
+            *hex,
+            out.clone(),
+        ),
+        _ => (
+            None,
+            None,
+            "auto".to_string(),
+            "utf-8".to_string(),
+            false,
+            false,
+            false,
+            None,
+        ),
+    };
+    let bytes = match read_raw(t, msg, data, file) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error(t, err.as_str(), None);
+            return;
+        }
+    };
+
+    let (encoding, reason) = match from.as_str() {
+        "auto" => _detect(&bytes),
+        label => match lookup(label) {
+            Ok(encoding) => (encoding, "given with --from"),
+            Err(err) => {
+                error(t, err.as_str(), None);
+                return;
+            }
+        },
+    };
+    if detect {
+        success(t, encoding.name(), Some("Encoding"), a.plain);
+        if !a.plain {
+            success(t, reason, Some("Reason"), false);
+        }
+        return;
+    }
+
+    let result = if fix_mojibake {
+        // Here --from names the charset the text was wrongly read as, not the one it's in
+        let misread = (from != "auto").then_some(encoding);
+        if misread.is_some_and(|encoding| !encoding.is_single_byte()) {
+            error(
+                t,
+                "--from has to name a single byte charset like windows-1252 with --fix-mojibake",
+                None,
+            );
+            return;
+        }
+        String::from_utf8(bytes)
+            .map_err(|_| "The text to fix has to be UTF-8".to_string())
+            .map(|text| match _fix_mojibake(&text, misread) {
+                Some((fixed, encoding)) => {
+                    if !a.plain {
+                        success(t, encoding.name(), Some("Misread as"), false);
+                    }
+                    fixed
+                }
+                None => {
+                    if !a.plain {
+                        error(t, "No mojibake found", Some("Warning"));
+                    }
+                    text
+                }
+            })
+    } else {
+        if from == "auto" && !a.plain {
+            success(t, encoding.name(), Some("Detected"), false);
+        }
+        decode(&bytes, encoding.name())
+    };
+    let result = result
+        .and_then(|text| encode(&text, &to))
+        .and_then(|(bytes, _)| write_decoded(t, a.plain, bytes, hex, out));
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+// Labels are matched the WHATWG way, so `latin1`, `iso-8859-1` and `cp1252` are all accepted
+pub fn lookup(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or(format!("Unknown charset '{label}'"))
+}
+
+pub fn decode(bytes: &[u8], label: &str) -> Result<String, String> {
+    let (text, _, had_errors) = lookup(label)?.decode(bytes);
+    if had_errors {
+        return Err(format!("The data isn't valid {label}"));
+    }
+    Ok(text.into_owned())
+}
+
+// Returns the bytes along with the name of the charset used. encoding_rs can only decode UTF-16,
+// so it's encoded here
+pub fn encode(text: &str, label: &str) -> Result<(Vec<u8>, String), String> {
+    let requested = lookup(label)?;
+    if requested == UTF_16LE || requested == UTF_16BE {
+        let bytes = text
+            .encode_utf16()
+            .flat_map(|unit| match requested == UTF_16LE {
+                true => unit.to_le_bytes(),
+                false => unit.to_be_bytes(),
+            })
+            .collect();
+        return Ok((bytes, requested.name().to_string()));
+    }
+    let (bytes, encoding, had_errors) = requested.encode(text);
+    if had_errors {
+        let c = text
+            .chars()
+            .find(|c| requested.encode(c.encode_utf8(&mut [0; 4])).2)
+            .unwrap_or_default();
+        return Err(format!(
+            "'{c}' (U+{:04X}) can't be represented in {label}",
+            c as u32
+        ));
+    }
+    let name = if encoding == requested {
+        label.trim().to_uppercase()
+    } else {
+        encoding.name().to_string()
+    };
+    Ok((bytes.into_owned(), name))
+}
+
+// A byte order mark or valid UTF-8 settles it, otherwise the guess is left to chardetng, the
+// detector Firefox uses
+fn _detect(bytes: &[u8]) -> (&'static Encoding, &'static str) {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return (encoding, "byte order mark");
+    }
+    // Latin text in UTF-16 has a zero byte before or after every character. Those are ASCII too,
+    // so this has to come first
+    let zeros = |parity: usize| {
+        bytes
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count()
+    };
+    let half = bytes.len() / 2;
+    if half > 0 && zeros(1) * 10 >= half * 7 && zeros(0) * 10 < half {
+        return (UTF_16LE, "zero bytes after most characters");
+    }
+    if half > 0 && zeros(0) * 10 >= half * 7 && zeros(1) * 10 < half {
+        return (UTF_16BE, "zero bytes before most characters");
+    }
+    if bytes.is_ascii() {
+        return (UTF_8, "only ASCII characters");
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return (UTF_8, "valid UTF-8");
+    }
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Allow);
+    detector.feed(bytes, true);
+    (
+        detector.guess(None, Utf8Detection::Deny),
+        "statistical guess",
+    )
+}
+
+// UTF-8 read as a single byte charset turns 'é' into 'Ã©'. Encoding the text back to that charset
+// gives the original bytes, and wherever those are valid UTF-8 the repair is taken. Text that was
+// fine to begin with doesn't form valid UTF-8 that way, so it's left alone. This is repeated to
+// undo text that went wrong more than once
+fn _fix_mojibake(
+    text: &str,
+    misread: Option<&'static Encoding>,
+) -> Option<(String, &'static Encoding)> {
+    let candidates = match misread {
+        Some(encoding) => vec![encoding],
+        None => vec![WINDOWS_1252, WINDOWS_1251, WINDOWS_1250],
+    };
+    for encoding in candidates {
+        let mut fixed = text.to_string();
+        while let Some(repaired) = repair(&fixed, encoding) {
+            fixed = repaired;
+        }
+        if fixed != text {
+            return Some((fixed, encoding));
+        }
+    }
+    None
+}
+
+fn repair(text: &str, encoding: &'static Encoding) -> Option<String> {
+    let mut output = String::new();
+    let mut changed = false;
+    let mut run: Vec<(u8, char)> = vec![];
+    let mut buffer = [0; 4];
+    for c in text.chars().map(Some).chain([None]) {
+        // Every character in a single byte charset is one byte, which keeps bytes and
+        // characters lined up
+        if let Some(c) = c {
+            let (bytes, _, unmappable) = encoding.encode(c.encode_utf8(&mut buffer));
+            if !unmappable && bytes.len() == 1 {
+                run.push((bytes[0], c));
+                continue;
+            }
+        }
+        let run_bytes: Vec<u8> = run.iter().map(|(byte, _)| *byte).collect();
+        let mut index = 0;
+        for chunk in run_bytes.utf8_chunks() {
+            let valid = chunk.valid();
+            changed |= !valid.is_ascii();
+            output.push_str(valid);
+            index += valid.len();
+            for (_, original) in &run[index..index + chunk.invalid().len()] {
+                output.push(*original);
+            }
+            index += chunk.invalid().len();
+        }
+        run.clear();
+        output.extend(c);
+    }
+    changed.then_some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{_detect, _fix_mojibake, encode};
+    use encoding_rs::{SHIFT_JIS, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1251, WINDOWS_1252};
+
+    #[test]
+    fn fix_mojibake() {
+        assert_eq!(
+            _fix_mojibake("CafÃ© Ã\u{a0} la carte", None),
+            Some(("Café à la carte".to_string(), WINDOWS_1252))
+        );
+        // Read wrongly twice
+        assert_eq!(
+            _fix_mojibake("CafÃƒÂ©", None),
+            Some(("Café".to_string(), WINDOWS_1252))
+        );
+        assert_eq!(
+            _fix_mojibake("РџСЂРёРІРµС‚", Some(WINDOWS_1251)),
+            Some(("Привет".to_string(), WINDOWS_1251))
+        );
+        assert_eq!(_fix_mojibake("Café déjà vu", None), None);
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(_detect(b"\xEF\xBB\xBFhi").0, UTF_8);
+        assert_eq!(_detect("café".as_bytes()).0, UTF_8);
+        assert_eq!(_detect(&encode("hello", "utf-16le").unwrap().0).0, UTF_16LE);
+        assert_eq!(_detect(&encode("hello", "utf-16be").unwrap().0).0, UTF_16BE);
+        let japanese = encode("こんにちは、世界。日本語のテキストです。", "shift_jis").unwrap();
+        assert_eq!(_detect(&japanese.0).0, SHIFT_JIS);
+    }
+
+    #[test]
+    fn unmappable_characters() {
+        assert_eq!(
+            encode("aéb", "iso-8859-5").unwrap_err(),
+            "'é' (U+00E9) can't be represented in iso-8859-5"
+        );
+    }
+}
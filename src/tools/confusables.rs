@@ -0,0 +1,94 @@
+// The Cyrillic, Greek and other letters most often used to imitate Latin ones in domains and
+// usernames, a small part of the Unicode confusables list. Letters like Cyrillic 'н' and 'т'
+// look like small capitals rather than lowercase Latin letters, so they aren't listed
+const LOOKALIKES: &[(char, char)] = &[
+    ('а', 'a'),
+    ('е', 'e'),
+    ('ё', 'e'),
+    ('һ', 'h'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ӏ', 'l'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('ԛ', 'q'),
+    ('с', 'c'),
+    ('ѕ', 's'),
+    ('у', 'y'),
+    ('ү', 'y'),
+    ('ԝ', 'w'),
+    ('х', 'x'),
+    ('ԁ', 'd'),
+    ('ɡ', 'g'),
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('Н', 'H'),
+    ('І', 'I'),
+    ('Ј', 'J'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Ѕ', 'S'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('Ү', 'Y'),
+    ('α', 'a'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+    ('ո', 'n'),
+    ('ս', 'u'),
+    ('օ', 'o'),
+    ('ı', 'i'),
+    ('ȷ', 'j'),
+    ('ɑ', 'a'),
+    ('ʏ', 'y'),
+];
+
+pub fn lookalike(c: char) -> Option<char> {
+    LOOKALIKES
+        .iter()
+        .find(|(confusable, _)| *confusable == c)
+        .map(|(_, latin)| *latin)
+}
+
+// Replaces every lookalike with the Latin letter it imitates
+pub fn skeleton(text: &str) -> String {
+    text.chars().map(|c| lookalike(c).unwrap_or(c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lookalike, skeleton};
+
+    #[test]
+    fn skeletons() {
+        assert_eq!(skeleton("раураl"), "paypal");
+        assert_eq!(skeleton("ΑΒΟ"), "ABO");
+        for c in ['н', 'в', 'к', 'м', 'т'] {
+            assert_eq!(lookalike(c), None);
+        }
+        assert_eq!(skeleton("нет"), "нeт");
+    }
+}
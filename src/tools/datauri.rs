@@ -0,0 +1,261 @@
+use super::_base_64_decode_bytes;
+use super::binary::{encode_bytes, write_bytes};
+use super::charset;
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use std::fs;
+use std::path::Path;
+use urlencoding::{decode_binary, encode_binary};
+
+pub fn data_uri_encode(a: &Args, t: &Term) {
+    let msg = "Enter the path of a file to encode:";
+    let (file, mime, percent) = match &a.tool {
+        Some(Tools::DataURIEncode {
+            file,
+            mime,
+            percent,
+        }) => (file.clone(), mime.clone(), *percent),
+        _ => (None, None, false),
+    };
+    let path = file.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, None, None)
+    });
+    let result = fs::read(&path)
+        .map_err(|err| format!("Error reading {path}: {err}"))
+        .map(|bytes| {
+            let mime = mime.unwrap_or_else(|| sniff(&bytes, &path));
+            _data_uri_encode(&bytes, &mime, percent)
+        });
+    match result {
+        Ok(uri) => success(t, uri.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn data_uri_decode(a: &Args, t: &Term) {
+    let msg = "Enter a data URI to decode:";
+    let (data, out) = match &a.tool {
+        Some(Tools::DataURIDecode { data, out }) => (data.clone(), out.clone()),
+        _ => (None, None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, None, None)
+    });
+    let uri = match DataURI::parse(&input) {
+        Ok(uri) => uri,
+        Err(err) => {
+            error(t, err.as_str(), None);
+            return;
+        }
+    };
+    if let Some(path) = out {
+        if let Err(err) = write_bytes(t, &uri.payload, "base64", Some(path)) {
+            error(t, err.as_str(), None);
+        }
+        return;
+    }
+
+    // Without a charset parameter the payload is read as UTF-8, the default for most text now
+    let text = match uri.parameters.iter().find(|(name, _)| name == "charset") {
+        Some((_, label)) => charset::decode(&uri.payload, label).ok(),
+        None => String::from_utf8(uri.payload.clone()).ok(),
+    };
+    if a.plain {
+        match text {
+            Some(text) => success(t, text.as_str(), None, true),
+            None => error(
+                t,
+                "The payload isn't text, use --out to write it to a file",
+                None,
+            ),
+        }
+        return;
+    }
+    success(t, uri.mime.as_str(), Some("MIME Type"), false);
+    for (name, value) in &uri.parameters {
+        success(t, value.as_str(), Some(name.as_str()), false);
+    }
+    let encoding = if uri.base64 { "base64" } else { "percent" };
+    success(t, encoding, Some("Encoding"), false);
+    success(
+        t,
+        format!("{} bytes", uri.payload.len()).as_str(),
+        Some("Size"),
+        false,
+    );
+    match text {
+        Some(text) => success(t, text.as_str(), Some("Data"), false),
+        None => success(
+            t,
+            "binary, use --out to write it to a file",
+            Some("Data"),
+            false,
+        ),
+    }
+}
+
+// Text is marked as UTF-8 so browsers don't fall back to US-ASCII
+fn _data_uri_encode(bytes: &[u8], mime: &str, percent: bool) -> String {
+    let is_text = mime.starts_with("text/") || mime == "image/svg+xml" || mime.ends_with("json");
+    let mut media_type = mime.to_string();
+    if is_text && !mime.contains("charset=") {
+        media_type.push_str(";charset=utf-8");
+    }
+    if percent {
+        format!("data:{media_type},{}", encode_binary(bytes))
+    } else {
+        format!("data:{media_type};base64,{}", encode_bytes(bytes, "base64"))
+    }
+}
+
+struct DataURI {
+    mime: String,
+    parameters: Vec<(String, String)>,
+    base64: bool,
+    payload: Vec<u8>,
+}
+
+impl DataURI {
+    // data:[<media type>][;<name>=<value>]*[;base64],<data>
+    fn parse(input: &str) -> Result<DataURI, String> {
+        let input = input.trim();
+        let rest = input
+            .get(..5)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+            .map(|_| &input[5..])
+            .ok_or("A data URI has to start with 'data:'")?;
+        let (header, data) = rest
+            .split_once(',')
+            .ok_or("A data URI needs a ',' between the media type and the data")?;
+
+        let mut parts = header.split(';').map(str::trim);
+        let mime = match parts.next() {
+            Some("") | None => "text/plain".to_string(),
+            Some(mime) => mime.to_ascii_lowercase(),
+        };
+        let mut parameters = vec![];
+        let mut base64 = false;
+        for part in parts {
+            match part.split_once('=') {
+                Some((name, value)) => {
+                    let value = decode_binary(value.as_bytes());
+                    parameters.push((
+                        name.to_ascii_lowercase(),
+                        String::from_utf8_lossy(&value).to_string(),
+                    ));
+                }
+                None if part.eq_ignore_ascii_case("base64") => base64 = true,
+                None if part.is_empty() => {}
+                None => return Err(format!("Invalid data URI parameter '{part}'")),
+            }
+        }
+
+        // Base64 data may itself be percent encoded, so that is undone first
+        let decoded = decode_binary(data.as_bytes()).into_owned();
+        let payload = if base64 {
+            _base_64_decode_bytes(&String::from_utf8_lossy(&decoded))?
+        } else {
+            decoded
+        };
+        Ok(DataURI {
+            mime,
+            parameters,
+            base64,
+            payload,
+        })
+    }
+}
+
+// ISO media files all start with an `ftyp` box, and its major brand tells them apart
+fn ftyp(brand: &[u8]) -> &'static str {
+    match brand {
+        b"avif" | b"avis" => "image/avif",
+        b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" => "image/heic",
+        b"mif1" | b"msf1" => "image/heif",
+        b"qt  " => "video/quicktime",
+        b"M4A " | b"M4B " => "audio/mp4",
+        b"crx " => "image/x-canon-cr3",
+        _ if brand.starts_with(b"3g2") => "video/3gpp2",
+        _ if brand.starts_with(b"3gp") => "video/3gpp",
+        b"isom" | b"iso2" | b"iso4" | b"iso5" | b"iso6" | b"mp41" | b"mp42" | b"avc1" | b"dash"
+        | b"M4V " | b"M4VH" | b"M4VP" | b"f4v " => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+// Checks the magic bytes first, then falls back to the file extension for text formats
+fn sniff(bytes: &[u8], path: &str) -> String {
+    let starts = |magic: &[u8]| bytes.starts_with(magic);
+    let at = |offset: usize, magic: &[u8]| bytes.get(offset..offset + magic.len()) == Some(magic);
+    let mime = if starts(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if starts(b"\xff\xd8\xff") {
+        "image/jpeg"
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        "image/gif"
+    } else if starts(b"RIFF") && at(8, b"WEBP") {
+        "image/webp"
+    } else if starts(b"RIFF") && at(8, b"WAVE") {
+        "audio/wav"
+    } else if at(4, b"ftyp") {
+        ftyp(bytes.get(8..12).unwrap_or_default())
+    } else if starts(b"BM") && at(6, b"\x00\x00\x00\x00") {
+        "image/bmp"
+    } else if starts(b"\x00\x00\x01\x00") {
+        "image/x-icon"
+    } else if starts(b"wOFF") {
+        "font/woff"
+    } else if starts(b"wOF2") {
+        "font/woff2"
+    } else if starts(b"\x00\x01\x00\x00") {
+        "font/ttf"
+    } else if starts(b"OTTO") {
+        "font/otf"
+    } else if starts(b"%PDF-") {
+        "application/pdf"
+    } else if starts(b"PK\x03\x04") {
+        "application/zip"
+    } else if starts(b"\x1f\x8b") {
+        "application/gzip"
+    } else if starts(b"\x00asm") {
+        "application/wasm"
+    } else if starts(b"ID3") || starts(b"\xff\xfb") {
+        "audio/mpeg"
+    } else if starts(b"OggS") {
+        "audio/ogg"
+    } else if starts(b"\x1a\x45\xdf\xa3") {
+        "video/webm"
+    } else {
+        ""
+    };
+    if !mime.is_empty() {
+        return mime.to_string();
+    }
+
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.trim_start_matches('\u{feff}').trim_start(),
+        Err(_) => return "application/octet-stream".to_string(),
+    };
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "svg" => "image/svg+xml",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "csv" => "text/csv",
+        _ if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) => {
+            "image/svg+xml"
+        }
+        _ if text.to_ascii_lowercase().starts_with("<!doctype html") => "text/html",
+        _ => "text/plain",
+    };
+    mime.to_string()
+}
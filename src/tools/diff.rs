@@ -0,0 +1,135 @@
+use super::formats::parse_file;
+use crate::{Args, Tools, error, print, read_input, success};
+use console::{Style, Term};
+use serde_json::{Value, json};
+
+pub enum Change {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+pub fn diff(a: &Args, t: &Term) {
+    let (left, right, patch) = match &a.tool {
+        Some(Tools::Diff { left, right, patch }) => (left.clone(), right.clone(), *patch),
+        _ => (None, None, false),
+    };
+    let left = left.unwrap_or_else(|| {
+        print(t, "Enter the path of the original document:");
+        read_input(t, None, None)
+    });
+    let right = right.unwrap_or_else(|| {
+        print(t, "Enter the path of the document to compare against:");
+        read_input(t, None, None)
+    });
+
+    let (left, right) = match (parse_file(&left), parse_file(&right)) {
+        (Ok((left, _)), Ok((right, _))) => (left, right),
+        (Err(err), _) | (_, Err(err)) => {
+            error(t, err.as_str(), None);
+            return;
+        }
+    };
+    let changes = diff_values(&left, &right);
+
+    if patch {
+        match serde_json::to_string_pretty(&to_patch(&changes)) {
+            Ok(json_str) => success(t, json_str.as_str(), None, true),
+            Err(err) => error(t, err.to_string().as_str(), None),
+        }
+        return;
+    }
+    if changes.is_empty() {
+        success(t, "The documents are equivalent", None, a.plain);
+        return;
+    }
+    let (green, red, yellow) = if a.plain {
+        (Style::new(), Style::new(), Style::new())
+    } else {
+        (
+            Style::new().green(),
+            Style::new().red(),
+            Style::new().yellow(),
+        )
+    };
+    for change in &changes {
+        let line = match change {
+            Change::Added(path, value) => green.apply_to(format!("+ {}: {value}", display(path))),
+            Change::Removed(path, value) => red.apply_to(format!("- {}: {value}", display(path))),
+            Change::Changed(path, old, new) => {
+                yellow.apply_to(format!("~ {}: {old} -> {new}", display(path)))
+            }
+        };
+        print(t, line.to_string().as_str());
+    }
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+pub fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn diff_values(left: &Value, right: &Value) -> Vec<Change> {
+    let mut changes = vec![];
+    _diff_values("", left, right, &mut changes);
+    changes
+}
+
+fn _diff_values(path: &str, left: &Value, right: &Value, changes: &mut Vec<Change>) {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            for (key, l_value) in l {
+                let child = format!("{path}/{}", escape_pointer(key));
+                match r.get(key) {
+                    Some(r_value) => _diff_values(&child, l_value, r_value, changes),
+                    None => changes.push(Change::Removed(child, l_value.clone())),
+                }
+            }
+            for (key, r_value) in r {
+                if !l.contains_key(key) {
+                    let child = format!("{path}/{}", escape_pointer(key));
+                    changes.push(Change::Added(child, r_value.clone()));
+                }
+            }
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            for (index, (l_value, r_value)) in l.iter().zip(r.iter()).enumerate() {
+                _diff_values(&format!("{path}/{index}"), l_value, r_value, changes);
+            }
+            for (index, r_value) in r.iter().enumerate().skip(l.len()) {
+                changes.push(Change::Added(format!("{path}/{index}"), r_value.clone()));
+            }
+            // Removed from the end first so every index in a generated patch is still valid
+            for (index, l_value) in l.iter().enumerate().skip(r.len()).rev() {
+                changes.push(Change::Removed(format!("{path}/{index}"), l_value.clone()));
+            }
+        }
+        _ => {
+            if left != right {
+                changes.push(Change::Changed(
+                    path.to_string(),
+                    left.clone(),
+                    right.clone(),
+                ));
+            }
+        }
+    }
+}
+
+pub fn to_patch(changes: &[Change]) -> Value {
+    Value::Array(
+        changes
+            .iter()
+            .map(|change| match change {
+                Change::Added(path, value) => json!({"op": "add", "path": path, "value": value}),
+                Change::Removed(path, _) => json!({"op": "remove", "path": path}),
+                Change::Changed(path, _, value) => {
+                    json!({"op": "replace", "path": path, "value": value})
+                }
+            })
+            .collect(),
+    )
+}
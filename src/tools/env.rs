@@ -0,0 +1,146 @@
+use super::flatten::flatten;
+use serde_json::{Map, Value};
+
+// Supports `export` prefixes, comments, single quoted literals and double quoted values with
+// escapes, both of which may span multiple lines
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut env = Map::new();
+    let mut lines = input.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let trimmed = trimmed
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map_or(trimmed, str::trim_start);
+        let (key, value) = trimmed
+            .split_once('=')
+            .ok_or(format!("Line {line_number}: expected KEY=VALUE"))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("Line {line_number}: invalid key '{key}'"));
+        }
+        let value = value.trim_start();
+
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut raw = value[1..].to_string();
+                loop {
+                    if let Some(end) = closing_quote(&raw, quote) {
+                        let rest = raw[end + 1..].trim();
+                        if !rest.is_empty() && !rest.starts_with('#') {
+                            return Err(format!(
+                                "Line {line_number}: unexpected '{rest}' after the closing quote"
+                            ));
+                        }
+                        raw.truncate(end);
+                        break;
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            raw.push('\n');
+                            raw.push_str(next);
+                        }
+                        None => {
+                            return Err(format!("Line {line_number}: unterminated quoted value"));
+                        }
+                    }
+                }
+                if quote == '"' { unescape(&raw) } else { raw }
+            }
+            _ => match value.find(" #") {
+                Some(comment) => value[..comment].trim_end().to_string(),
+                None => value.trim_end().to_string(),
+            },
+        };
+        env.insert(key.to_string(), Value::String(value));
+    }
+    Ok(Value::Object(env))
+}
+
+fn closing_quote(raw: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in raw.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn unescape(raw: &str) -> String {
+    let mut out = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// Nested keys are joined with `_` and upper cased, so `db: {host: x}` becomes `DB_HOST=x`
+pub fn emit(value: &Value) -> Result<String, String> {
+    let flat = match flatten(value, "_", false) {
+        Value::Object(flat) => flat,
+        _ => return Err("Only an object can be converted to .env".to_string()),
+    };
+    let mut lines = vec![];
+    for (key, value) in &flat {
+        let key: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let value = match value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        lines.push(format!("{key}={}", quote(&value)));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn quote(value: &str) -> String {
+    let plain = value.chars().all(|c| {
+        c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':' | ',' | '@' | '+')
+    });
+    if plain {
+        return value.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '$' => quoted.push_str("\\$"),
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,905 @@
+use super::_text_input;
+use crate::{Args, Tools, error, success};
+use console::Term;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub fn escape(a: &Args, t: &Term) {
+    let msg = "Enter a string to escape:";
+    let (data, lang, ascii, raw) = match &a.tool {
+        Some(Tools::Escape {
+            data,
+            lang,
+            ascii,
+            raw,
+        }) => (data.clone(), lang.clone(), *ascii, *raw),
+        _ => (None, "json".to_string(), false, false),
+    };
+    let input = _text_input(t, msg, data);
+
+    match _escape(&input, &lang, ascii, raw) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn unescape(a: &Args, t: &Term) {
+    let msg = "Enter a string literal to unescape:";
+    let (data, lang) = match &a.tool {
+        Some(Tools::Unescape { data, lang }) => (data.clone(), lang.clone()),
+        _ => (None, "json".to_string()),
+    };
+    let input = _text_input(t, msg, data);
+
+    match _unescape(&input, &lang) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), Some("Error unescaping string")),
+    }
+}
+
+// Languages with string literals get the quotes too, so the result can be pasted as it is
+fn _escape(input: &str, lang: &str, ascii: bool, raw: bool) -> Result<String, String> {
+    if raw {
+        return match lang {
+            "c" => Ok(c_raw(input)),
+            "rust" => Ok(rust_raw(input)),
+            "python" => python_raw(input),
+            "shell" | "powershell" | "sql" => Err(format!(
+                "Single quoted {lang} strings are already raw, leave out --raw"
+            )),
+            _ => Err(format!("{lang} has no raw strings")),
+        };
+    }
+    match lang {
+        "json" => Ok(quoted('"', input, |c| match c {
+            '"' | '\\' => Some(format!("\\{c}")),
+            c if c < ' ' || (ascii && !c.is_ascii()) => json_control(c).or(Some(utf16(c))),
+            _ => None,
+        })),
+        "c" => Ok(quoted('"', input, |c| match c {
+            '"' | '\\' => Some(format!("\\{c}")),
+            // Octal is used because a \x escape would swallow any hex digits that follow
+            c if c.is_ascii_control() => c_control(c).or(Some(format!("\\{:03o}", c as u32))),
+            c if ascii && !c.is_ascii() => Some(long_unicode(c)),
+            _ => None,
+        })),
+        "rust" => Ok(quoted('"', input, |c| match c {
+            '"' | '\\' => Some(format!("\\{c}")),
+            '\0' => Some("\\0".to_string()),
+            c if c.is_control() || (ascii && !c.is_ascii()) => {
+                json_control(c).or(Some(format!("\\u{{{:x}}}", c as u32)))
+            }
+            _ => None,
+        })),
+        "python" => {
+            // Like repr(), the other quote is picked when the text has single quotes in it
+            let quote = match input.contains('\'') && !input.contains('"') {
+                true => '"',
+                false => '\'',
+            };
+            Ok(quoted(quote, input, |c| match c {
+                '\\' => Some("\\\\".to_string()),
+                c if c == quote => Some(format!("\\{c}")),
+                c if c.is_control() || (ascii && !c.is_ascii()) => {
+                    json_control(c).or(Some(match c as u32 {
+                        code @ 0..0x100 => format!("\\x{code:02x}"),
+                        code => short_or_long_unicode(code),
+                    }))
+                }
+                _ => None,
+            }))
+        }
+        "java" => Ok(quoted('"', input, |c| match c {
+            '"' | '\\' => Some(format!("\\{c}")),
+            c if c.is_control() || (ascii && !c.is_ascii()) => json_control(c).or(Some(utf16(c))),
+            _ => None,
+        })),
+        "shell" => Ok(shell(input, ascii)),
+        "powershell" => Ok(powershell(input, ascii)),
+        "sql" => Ok(sql(input, ascii)),
+        "regex" => Ok(input
+            .chars()
+            .map(|c| match c {
+                c if "\\.+*?()|[]{}^$#&-~".contains(c) => format!("\\{c}"),
+                c if c.is_control() || (ascii && !c.is_ascii()) => {
+                    json_control(c).unwrap_or(format!("\\x{{{:X}}}", c as u32))
+                }
+                c => c.to_string(),
+            })
+            .collect()),
+        "csv" if ascii && !input.is_ascii() => {
+            Err("CSV has no escapes for characters outside ASCII".to_string())
+        }
+        "csv" => {
+            let needs_quotes = input.contains([',', '"', '\n', '\r'])
+                || input.starts_with(' ')
+                || input.ends_with(' ');
+            match needs_quotes {
+                true => Ok(format!("\"{}\"", input.replace('"', "\"\""))),
+                false => Ok(input.to_string()),
+            }
+        }
+        "xml" => xml(input, ascii),
+        _ => Err(format!("Unknown language '{lang}'")),
+    }
+}
+
+fn quoted(quote: char, input: &str, escape: impl Fn(char) -> Option<String>) -> String {
+    let mut literal = String::from(quote);
+    for c in input.chars() {
+        match escape(c) {
+            Some(escaped) => literal.push_str(&escaped),
+            None => literal.push(c),
+        }
+    }
+    literal.push(quote);
+    literal
+}
+
+// The short escapes JSON, Rust, Python and Java all share
+fn json_control(c: char) -> Option<String> {
+    match c {
+        '\n' => Some("\\n".to_string()),
+        '\r' => Some("\\r".to_string()),
+        '\t' => Some("\\t".to_string()),
+        _ => None,
+    }
+}
+
+fn c_control(c: char) -> Option<String> {
+    match c {
+        '\x07' => Some("\\a".to_string()),
+        '\x08' => Some("\\b".to_string()),
+        '\x0b' => Some("\\v".to_string()),
+        '\x0c' => Some("\\f".to_string()),
+        c => json_control(c),
+    }
+}
+
+// JSON and Java only have \uXXXX, so characters above U+FFFF become a surrogate pair
+fn utf16(c: char) -> String {
+    c.encode_utf16(&mut [0; 2])
+        .iter()
+        .map(|unit| format!("\\u{unit:04x}"))
+        .collect()
+}
+
+fn long_unicode(c: char) -> String {
+    format!("\\U{:08X}", c as u32)
+}
+
+fn short_or_long_unicode(code: u32) -> String {
+    match code {
+        0..0x10000 => format!("\\u{code:04x}"),
+        code => format!("\\U{code:08x}"),
+    }
+}
+
+// C++11 raw strings end at )delimiter", so a delimiter is only added when that shows up
+fn c_raw(input: &str) -> String {
+    let mut delimiter = String::new();
+    while input.contains(&format!("){delimiter}\"")) {
+        delimiter.push('x');
+    }
+    format!("R\"{delimiter}({input}){delimiter}\"")
+}
+
+fn rust_raw(input: &str) -> String {
+    let mut hashes = String::new();
+    while input.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{input}\"{hashes}")
+}
+
+fn python_raw(input: &str) -> Result<String, String> {
+    let trailing = input.len() - input.trim_end_matches('\\').len();
+    let quote = ['\'', '"']
+        .into_iter()
+        .find(|quote| !input.contains(*quote));
+    match quote {
+        Some(quote) if trailing.is_multiple_of(2) && !input.contains(['\n', '\r']) => {
+            Ok(format!("r{quote}{input}{quote}"))
+        }
+        _ => Err(
+            "Python raw strings can't hold both quotes, line breaks or an odd number of trailing backslashes"
+                .to_string(),
+        ),
+    }
+}
+
+// Simple words are left alone, anything else is single quoted. Control characters need the
+// $'...' form, which also has Unicode escapes
+fn shell(input: &str, ascii: bool) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-./=:,@%+".contains(c);
+    if !input.is_empty() && input.chars().all(safe) {
+        return input.to_string();
+    }
+    if !ascii && !input.chars().any(char::is_control) {
+        return format!("'{}'", input.replace('\'', "'\\''"));
+    }
+    let ansi = quoted('\'', input, |c| match c {
+        '\'' | '\\' => Some(format!("\\{c}")),
+        '\x1b' => Some("\\e".to_string()),
+        c if c.is_ascii_control() => c_control(c).or(Some(format!("\\x{:02x}", c as u32))),
+        c if c.is_control() || (ascii && !c.is_ascii()) => Some(short_or_long_unicode(c as u32)),
+        _ => None,
+    });
+    format!("${ansi}")
+}
+
+// PowerShell also treats the typographic quotes as quotes, so those are doubled or escaped too
+fn powershell(input: &str, ascii: bool) -> String {
+    if !ascii && !input.chars().any(char::is_control) {
+        let mut literal = String::from('\'');
+        for c in input.chars() {
+            if matches!(c, '\'' | '‘' | '’' | '‚' | '‛') {
+                literal.push(c);
+            }
+            literal.push(c);
+        }
+        literal.push('\'');
+        return literal;
+    }
+    quoted('"', input, |c| match c {
+        '`' | '"' | '$' | '“' | '”' | '„' => Some(format!("`{c}")),
+        '\0' => Some("`0".to_string()),
+        '\x07' => Some("`a".to_string()),
+        '\x08' => Some("`b".to_string()),
+        '\x1b' => Some("`e".to_string()),
+        '\x0c' => Some("`f".to_string()),
+        '\n' => Some("`n".to_string()),
+        '\r' => Some("`r".to_string()),
+        '\t' => Some("`t".to_string()),
+        '\x0b' => Some("`v".to_string()),
+        c if c.is_control() || (ascii && !c.is_ascii()) => Some(format!("`u{{{:X}}}", c as u32)),
+        _ => None,
+    })
+}
+
+// --ascii uses the standard U&'...' form, which PostgreSQL and others accept
+fn sql(input: &str, ascii: bool) -> String {
+    if !ascii {
+        return format!("'{}'", input.replace('\'', "''"));
+    }
+    let literal = quoted('\'', input, |c| match c {
+        '\'' => Some("''".to_string()),
+        '\\' => Some("\\\\".to_string()),
+        c if c.is_control() || !c.is_ascii() => Some(match c as u32 {
+            code @ 0..0x10000 => format!("\\{code:04X}"),
+            code => format!("\\+{code:06X}"),
+        }),
+        _ => None,
+    });
+    format!("U&{literal}")
+}
+
+fn xml(input: &str, ascii: bool) -> Result<String, String> {
+    let mut escaped = String::new();
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // A literal carriage return would be turned into a line feed by the parser
+            '\r' => escaped.push_str("&#xD;"),
+            '\t' | '\n' => escaped.push(c),
+            // Tab, line feed and carriage return are the only C0 controls allowed, DEL is fine
+            '\0'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {
+                return Err(format!("XML 1.0 can't contain U+{:04X}", c as u32));
+            }
+            c if ascii && !c.is_ascii() => escaped.push_str(&format!("&#x{:X};", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+// The quotes around a literal are optional, so either the whole literal or only the text
+// between the quotes can be pasted
+fn _unescape(input: &str, lang: &str) -> Result<String, String> {
+    let input = input.trim_end_matches(['\n', '\r']);
+    match lang {
+        "json" => {
+            let literal = match input.trim().starts_with('"') {
+                true => input.trim().to_string(),
+                false => format!("\"{input}\""),
+            };
+            serde_json::from_str::<String>(&literal).map_err(|err| err.to_string())
+        }
+        "c" => {
+            let trimmed = input.trim();
+            if let Some(rest) = trimmed.strip_prefix("R\"")
+                && let Some((delimiter, rest)) = rest.split_once('(')
+                && let Some(text) = rest.strip_suffix(&format!("){delimiter}\""))
+            {
+                return Ok(text.to_string());
+            }
+            let body = ["u8", "L", "u", "U"]
+                .iter()
+                .find_map(|prefix| trimmed.strip_prefix(prefix))
+                .filter(|rest| rest.starts_with('"'))
+                .map_or_else(|| unquote(input, &["\""]), |rest| unquote(rest, &["\""]));
+            backslashes(body, Dialect::C)
+        }
+        "rust" => {
+            let trimmed = input.trim();
+            if let Some(rest) = trimmed.strip_prefix('r') {
+                let hashes = rest.len() - rest.trim_start_matches('#').len();
+                let fence = format!("\"{}", "#".repeat(hashes));
+                if let Some(text) = rest[hashes..]
+                    .strip_prefix('"')
+                    .and_then(|text| text.strip_suffix(&fence))
+                {
+                    return Ok(text.to_string());
+                }
+            }
+            backslashes(unquote(input, &["\""]), Dialect::Rust)
+        }
+        "python" => {
+            let trimmed = input.trim();
+            let prefix_length = trimmed
+                .chars()
+                .take_while(|c| "rRbBuUfF".contains(*c))
+                .count();
+            let (prefix, rest) = trimmed.split_at(prefix_length);
+            let quotes = ["\"\"\"", "'''", "\"", "'"];
+            if !rest.starts_with(['\'', '"']) {
+                return backslashes(input, Dialect::Python);
+            }
+            let body = unquote(rest, &quotes);
+            match prefix.contains(['r', 'R']) {
+                true => Ok(body.to_string()),
+                false => backslashes(body, Dialect::Python),
+            }
+        }
+        "java" => {
+            let trimmed = input.trim();
+            // Text blocks start on the line after the opening quotes
+            if let Some(block) = trimmed
+                .strip_prefix("\"\"\"")
+                .and_then(|block| block.strip_suffix("\"\"\""))
+            {
+                let block = block.split_once('\n').map_or(block, |(_, rest)| rest);
+                return backslashes(block, Dialect::Java);
+            }
+            backslashes(unquote(input, &["\""]), Dialect::Java)
+        }
+        "shell" => unescape_shell(input.trim()),
+        "powershell" => unescape_powershell(input.trim()),
+        "sql" => unescape_sql(input.trim()),
+        "regex" => unescape_regex(input),
+        "csv" => {
+            let trimmed = input.trim();
+            match trimmed.len() > 1 && trimmed.starts_with('"') && trimmed.ends_with('"') {
+                true => Ok(trimmed[1..trimmed.len() - 1].replace("\"\"", "\"")),
+                false => Ok(input.to_string()),
+            }
+        }
+        "xml" => unescape_xml(input),
+        _ => Err(format!("Unknown language '{lang}'")),
+    }
+}
+
+fn unquote<'a>(input: &'a str, quotes: &[&str]) -> &'a str {
+    let trimmed = input.trim();
+    quotes
+        .iter()
+        .find_map(|quote| {
+            trimmed
+                .strip_prefix(quote)
+                .and_then(|rest| rest.strip_suffix(quote))
+        })
+        .unwrap_or(input)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Dialect {
+    C,
+    Rust,
+    Python,
+    Java,
+    // Bash's $'...' strings
+    Ansi,
+    // PostgreSQL's E'...' strings
+    Postgres,
+}
+
+// C's \x and octal escapes stand for bytes, everywhere else they stand for code points
+fn backslashes(input: &str, dialect: Dialect) -> Result<String, String> {
+    let mut bytes = vec![];
+    let mut high_surrogate: Option<u32> = None;
+    let mut chars = input.chars().peekable();
+    let push = |bytes: &mut Vec<u8>, c: char| bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            push(&mut bytes, c);
+            continue;
+        }
+        let Some(escape) = chars.next() else {
+            return Err("The string ends with a lone backslash".to_string());
+        };
+        let simple = match escape {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            '\\' | '\'' | '"' => Some(escape),
+            '0' if dialect == Dialect::Rust => Some('\0'),
+            'a' if dialect != Dialect::Rust && dialect != Dialect::Java => Some('\x07'),
+            'b' if dialect != Dialect::Rust => Some('\x08'),
+            'f' if dialect != Dialect::Rust => Some('\x0c'),
+            'v' if dialect != Dialect::Rust && dialect != Dialect::Java => Some('\x0b'),
+            'e' | 'E' if matches!(dialect, Dialect::C | Dialect::Ansi) => Some('\x1b'),
+            's' if dialect == Dialect::Java => Some(' '),
+            '?' if matches!(dialect, Dialect::C | Dialect::Ansi) => Some('?'),
+            _ => None,
+        };
+        if let Some(simple) = simple {
+            push(&mut bytes, simple);
+            continue;
+        }
+        let code = match escape {
+            // A backslash at the end of a line continues the string on the next one
+            '\n' => {
+                if dialect == Dialect::Rust {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                }
+                continue;
+            }
+            '0'..='7' if dialect != Dialect::Rust => {
+                let mut digits = escape.to_string();
+                while digits.len() < 3
+                    && let Some(digit) = chars.next_if(|c| ('0'..='7').contains(c))
+                {
+                    digits.push(digit);
+                }
+                let value = u32::from_str_radix(&digits, 8).unwrap_or(0);
+                if dialect == Dialect::C || dialect == Dialect::Ansi || dialect == Dialect::Postgres
+                {
+                    bytes.push(value as u8);
+                    continue;
+                }
+                value
+            }
+            'x' if dialect != Dialect::Java => {
+                let value = match dialect {
+                    Dialect::C => hex_digits(&mut chars, 1, 8)?,
+                    Dialect::Rust | Dialect::Python => hex_digits(&mut chars, 2, 2)?,
+                    _ => hex_digits(&mut chars, 1, 2)?,
+                };
+                if dialect == Dialect::C && value > 0xff {
+                    return Err(format!("\\x{value:x} is too big for a byte"));
+                }
+                if dialect == Dialect::Rust && value > 0x7f {
+                    return Err(format!(
+                        "\\x{value:02x} is above \\x7f, use \\u{{{value:x}}} instead"
+                    ));
+                }
+                if dialect == Dialect::C || dialect == Dialect::Ansi || dialect == Dialect::Postgres
+                {
+                    bytes.push(value as u8);
+                    continue;
+                }
+                value
+            }
+            'u' if dialect == Dialect::Rust => {
+                if chars.next() != Some('{') {
+                    return Err("Expected '{' after \\u".to_string());
+                }
+                let value = hex_digits(&mut chars, 1, 6)?;
+                if chars.next() != Some('}') {
+                    return Err("Expected '}' to end the \\u{...} escape".to_string());
+                }
+                value
+            }
+            'u' => {
+                // Java allows any number of u's, e.g. \uuu00e9
+                if dialect == Dialect::Java {
+                    while chars.next_if_eq(&'u').is_some() {}
+                }
+                let value = hex_digits(&mut chars, 4, 4)?;
+                if (0xd800..0xdc00).contains(&value) {
+                    high_surrogate = Some(value);
+                    continue;
+                }
+                value
+            }
+            'U' if dialect != Dialect::Rust && dialect != Dialect::Java => {
+                hex_digits(&mut chars, 8, 8)?
+            }
+            'N' if dialect == Dialect::Python => {
+                if chars.next() != Some('{') {
+                    return Err("Expected '{' after \\N".to_string());
+                }
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let c = unicode_names2::character(&name)
+                    .ok_or(format!("Unknown character name '{name}'"))?;
+                c as u32
+            }
+            // Python keeps unknown escapes as they are
+            other if dialect == Dialect::Python || dialect == Dialect::Ansi => {
+                push(&mut bytes, '\\');
+                push(&mut bytes, other);
+                continue;
+            }
+            other if dialect == Dialect::Postgres => {
+                push(&mut bytes, other);
+                continue;
+            }
+            other => return Err(format!("Unknown escape '\\{other}'")),
+        };
+        let code = match high_surrogate.take() {
+            Some(high) if (0xdc00..0xe000).contains(&code) => {
+                0x10000 + ((high - 0xd800) << 10) + (code - 0xdc00)
+            }
+            Some(high) => return Err(format!("Unpaired surrogate \\u{high:04x}")),
+            None => code,
+        };
+        let c = char::from_u32(code).ok_or(format!("U+{code:X} isn't a valid character"))?;
+        push(&mut bytes, c);
+    }
+    if let Some(high) = high_surrogate {
+        return Err(format!("Unpaired surrogate \\u{high:04x}"));
+    }
+    String::from_utf8(bytes).map_err(|_| "The escaped bytes aren't valid UTF-8 text".to_string())
+}
+
+fn hex_digits(chars: &mut Peekable<Chars>, min: usize, max: usize) -> Result<u32, String> {
+    let mut digits = String::new();
+    while digits.len() < max
+        && let Some(digit) = chars.next_if(char::is_ascii_hexdigit)
+    {
+        digits.push(digit);
+    }
+    if digits.len() < min {
+        return Err(format!("Expected {min} hex digits in an escape"));
+    }
+    u32::from_str_radix(&digits, 16).map_err(|err| err.to_string())
+}
+
+// A shell word can mix quoting styles, e.g. 'it'\''s' or "$HOME"/'dir'
+fn unescape_shell(input: &str) -> Result<String, String> {
+    let mut output = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => output.push_str(&until(&mut chars, '\'')?),
+            '$' if chars.next_if_eq(&'\'').is_some() => {
+                let mut body = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            body.push('\\');
+                            body.extend(chars.next());
+                        }
+                        Some('\'') => break,
+                        Some(c) => body.push(c),
+                        None => return Err("Missing the closing quote".to_string()),
+                    }
+                }
+                output.push_str(&backslashes(&body, Dialect::Ansi)?);
+            }
+            '"' => loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('$' | '`' | '"' | '\\')) => output.push(c),
+                        Some('\n') => {}
+                        Some(c) => {
+                            output.push('\\');
+                            output.push(c);
+                        }
+                        None => return Err("Missing the closing quote".to_string()),
+                    },
+                    Some('"') => break,
+                    Some(c) => output.push(c),
+                    None => return Err("Missing the closing quote".to_string()),
+                }
+            },
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(c) => output.push(c),
+            },
+            c => output.push(c),
+        }
+    }
+    Ok(output)
+}
+
+fn until(chars: &mut Peekable<Chars>, quote: char) -> Result<String, String> {
+    let mut text = String::new();
+    for c in chars.by_ref() {
+        if c == quote {
+            return Ok(text);
+        }
+        text.push(c);
+    }
+    Err("Missing the closing quote".to_string())
+}
+
+fn unescape_powershell(input: &str) -> Result<String, String> {
+    let single = ['\'', '‘', '’', '‚', '‛'];
+    let double = ['"', '“', '”', '„'];
+    let mut chars = input.chars().peekable();
+    match chars.next() {
+        Some(c) if single.contains(&c) => {
+            let mut output = String::new();
+            while let Some(c) = chars.next() {
+                if single.contains(&c) {
+                    match chars.next_if(|next| single.contains(next)) {
+                        Some(next) => output.push(next),
+                        None => return Ok(output),
+                    }
+                } else {
+                    output.push(c);
+                }
+            }
+            Err("Missing the closing quote".to_string())
+        }
+        Some(c) if double.contains(&c) => {
+            let mut output = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '`' => output.push_str(&backtick(&mut chars)?),
+                    c if double.contains(&c) => match chars.next_if(|next| double.contains(next)) {
+                        Some(next) => output.push(next),
+                        None => return Ok(output),
+                    },
+                    c => output.push(c),
+                }
+            }
+            Err("Missing the closing quote".to_string())
+        }
+        _ => {
+            let mut output = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '`' => output.push_str(&backtick(&mut chars)?),
+                    c => output.push(c),
+                }
+            }
+            Ok(output)
+        }
+    }
+}
+
+fn backtick(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let c = match chars.next() {
+        Some('0') => '\0',
+        Some('a') => '\x07',
+        Some('b') => '\x08',
+        Some('e') => '\x1b',
+        Some('f') => '\x0c',
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('t') => '\t',
+        Some('v') => '\x0b',
+        Some('u') if chars.next_if_eq(&'{').is_some() => {
+            let code = hex_digits(chars, 1, 6)?;
+            if chars.next() != Some('}') {
+                return Err("Expected '}' to end the `u{...} escape".to_string());
+            }
+            char::from_u32(code).ok_or(format!("U+{code:X} isn't a valid character"))?
+        }
+        Some(c) => c,
+        None => return Ok(String::new()),
+    };
+    Ok(c.to_string())
+}
+
+fn unescape_sql(input: &str) -> Result<String, String> {
+    let lower = input.to_lowercase();
+    let (body, kind) = if lower.starts_with("u&'") {
+        (&input[2..], 'u')
+    } else if lower.starts_with("e'") {
+        (&input[1..], 'e')
+    } else {
+        (input, ' ')
+    };
+    let body = match body.len() > 1 && body.starts_with('\'') && body.ends_with('\'') {
+        true => &body[1..body.len() - 1],
+        false => body,
+    };
+    let body = body.replace("''", "'");
+    match kind {
+        'e' => backslashes(&body, Dialect::Postgres),
+        'u' => {
+            let mut output = String::new();
+            let mut chars = body.chars().peekable();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    output.push(c);
+                    continue;
+                }
+                let code = match chars.peek() {
+                    Some('\\') => {
+                        chars.next();
+                        output.push('\\');
+                        continue;
+                    }
+                    Some('+') => {
+                        chars.next();
+                        hex_digits(&mut chars, 6, 6)?
+                    }
+                    _ => hex_digits(&mut chars, 4, 4)?,
+                };
+                output.push(
+                    char::from_u32(code).ok_or(format!("U+{code:X} isn't a valid character"))?,
+                );
+            }
+            Ok(output)
+        }
+        _ => Ok(body),
+    }
+}
+
+// Only escapes that stand for a single character can be undone, not classes like \d
+fn unescape_regex(input: &str) -> Result<String, String> {
+    let mut output = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        let c = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('f') => '\x0c',
+            Some('v') => '\x0b',
+            Some('a') => '\x07',
+            Some('0') => '\0',
+            Some(escape @ ('x' | 'u')) => {
+                let code = match chars.next_if_eq(&'{') {
+                    Some(_) => {
+                        let code = hex_digits(&mut chars, 1, 8)?;
+                        if chars.next() != Some('}') {
+                            return Err(format!(
+                                "Expected '}}' to end the \\{escape}{{...}} escape"
+                            ));
+                        }
+                        code
+                    }
+                    None if escape == 'x' => hex_digits(&mut chars, 2, 2)?,
+                    None => hex_digits(&mut chars, 4, 4)?,
+                };
+                char::from_u32(code).ok_or(format!("U+{code:X} isn't a valid character"))?
+            }
+            Some(c) if c.is_alphanumeric() => {
+                return Err(format!(
+                    "'\\{c}' is a class or an assertion, not a single character"
+                ));
+            }
+            Some(c) => c,
+            None => return Err("The pattern ends with a lone backslash".to_string()),
+        };
+        output.push(c);
+    }
+    Ok(output)
+}
+
+// XML only knows five named entities and is strict about a bare '&'
+fn unescape_xml(input: &str) -> Result<String, String> {
+    let mut output = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or(format!("Unescaped '&' in '{}'", &rest[start..]))?;
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match (entity.strip_prefix("#x"), entity.strip_prefix('#')) {
+                    (Some(hex), _) => u32::from_str_radix(hex, 16).ok(),
+                    (_, Some(decimal)) => decimal.parse().ok(),
+                    _ => None,
+                };
+                code.and_then(char::from_u32)
+                    .ok_or(format!("Unknown entity '&{entity};'"))?
+            }
+        };
+        output.push(c);
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{_escape, _unescape};
+
+    const LANGS: [&str; 11] = [
+        "json",
+        "c",
+        "rust",
+        "python",
+        "java",
+        "shell",
+        "powershell",
+        "sql",
+        "regex",
+        "csv",
+        "xml",
+    ];
+
+    #[test]
+    fn round_trip() {
+        let samples = [
+            "plain",
+            "it's \"quoted\" \\ back",
+            "tab\tnew\nline\r",
+            "café 😀 \u{2028}",
+            "$HOME `cmd` ${x}",
+            "a.b*c+d?(e)[f]{g}|h^i",
+            "<tag attr='1'> & </tag>",
+            "comma, \"quote\"",
+        ];
+        for lang in LANGS {
+            for ascii in [false, true] {
+                for sample in samples {
+                    // CSV has no escapes, so it can only keep text outside ASCII as it is
+                    if lang == "csv" && ascii && !sample.is_ascii() {
+                        assert!(_escape(sample, lang, ascii, false).is_err());
+                        continue;
+                    }
+                    let escaped = _escape(sample, lang, ascii, false).unwrap();
+                    assert_eq!(
+                        _unescape(&escaped, lang).unwrap(),
+                        sample,
+                        "{lang} ascii={ascii}: {escaped}"
+                    );
+                    if ascii {
+                        assert!(escaped.is_ascii(), "{lang}: {escaped}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn raw_strings() {
+        for lang in ["c", "rust", "python"] {
+            let sample = r#"C:\path\"quoted" )" "#;
+            let escaped = _escape(sample, lang, false, true).unwrap();
+            assert_eq!(
+                _unescape(&escaped, lang).unwrap(),
+                sample,
+                "{lang}: {escaped}"
+            );
+        }
+        assert!(_escape("x", "json", false, true).is_err());
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(
+            _unescape(r#""caf\u00e9\t\ud83d\ude00""#, "java").unwrap(),
+            "café\t😀"
+        );
+        assert_eq!(_unescape(r"'it'\''s'", "shell").unwrap(), "it's");
+        assert_eq!(_unescape(r"\N{BULLET}", "python").unwrap(), "•");
+        assert_eq!(_unescape("E'a\\nb'", "sql").unwrap(), "a\nb");
+        assert!(_unescape(r#""\q""#, "json").is_err());
+        assert!(_unescape(r"\u{110000}", "rust").is_err());
+        assert!(_unescape(r"\xZ", "c").is_err());
+    }
+
+    #[test]
+    fn xml_characters() {
+        assert_eq!(
+            _escape("a\u{7f}b\r", "xml", false, false).unwrap(),
+            "a\u{7f}b&#xD;"
+        );
+        assert!(_escape("a\u{1}b", "xml", false, false).is_err());
+        assert!(_escape("\u{fffe}", "xml", false, false).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use super::formats::{parse_any, render};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+pub fn json_flatten(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to flatten (type 'END' on a new line to stop input):";
+    let (separator, arrays, data) = match &a.tool {
+        Some(Tools::JSONFlatten {
+            separator,
+            arrays,
+            data,
+        }) => (separator.clone(), arrays.clone(), data.clone()),
+        _ => (".".to_string(), "index".to_string(), None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+
+    let result = parse_any(&input).and_then(|(value, format)| {
+        render(&flatten(&value, &separator, arrays == "bracket"), format)
+    });
+    match result {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_unflatten(a: &Args, t: &Term) {
+    let msg =
+        "Enter some flat JSON, YAML or TOML to unflatten (type 'END' on a new line to stop input):";
+    let (separator, arrays, data) = match &a.tool {
+        Some(Tools::JSONUnflatten {
+            separator,
+            arrays,
+            data,
+        }) => (separator.clone(), arrays.clone(), data.clone()),
+        _ => (".".to_string(), "index".to_string(), None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+
+    let result = parse_any(&input).and_then(|(value, format)| {
+        render(&unflatten(&value, &separator, arrays == "bracket")?, format)
+    });
+    match result {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn flatten(value: &Value, separator: &str, brackets: bool) -> Value {
+    let mut flat = Map::new();
+    _flatten("", value, separator, brackets, &mut flat);
+    Value::Object(flat)
+}
+
+fn _flatten(
+    prefix: &str,
+    value: &Value,
+    separator: &str,
+    brackets: bool,
+    flat: &mut Map<String, Value>,
+) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}{separator}{key}")
+        }
+    };
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                _flatten(&join(key), value, separator, brackets, flat);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, value) in items.iter().enumerate() {
+                let key = if brackets {
+                    format!("{prefix}[{index}]")
+                } else {
+                    join(&index.to_string())
+                };
+                _flatten(&key, value, separator, brackets, flat);
+            }
+        }
+        // Empty objects and arrays are kept as values so they survive a round trip
+        _ => {
+            flat.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+// Indexes sort before keys and numerically, so array items are added in order
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    Index(usize),
+    Key(String),
+}
+
+impl Segment {
+    fn name(&self) -> String {
+        match self {
+            Segment::Index(index) => index.to_string(),
+            Segment::Key(name) => name.clone(),
+        }
+    }
+}
+
+fn split_key(key: &str, separator: &str, brackets: bool) -> Result<Vec<Segment>, String> {
+    let mut segments = vec![];
+    let parts: Vec<&str> = if separator.is_empty() {
+        vec![key]
+    } else {
+        key.split(separator).collect()
+    };
+    for part in parts {
+        if !brackets {
+            segments.push(match part.parse::<usize>() {
+                Ok(index) => Segment::Index(index),
+                Err(_) => Segment::Key(part.to_string()),
+            });
+            continue;
+        }
+        let (name, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !name.is_empty() || rest.is_empty() {
+            segments.push(Segment::Key(name.to_string()));
+        }
+        while let Some(inner) = rest.strip_prefix('[') {
+            let end = inner
+                .find(']')
+                .ok_or(format!("Unclosed '[' in key '{key}'"))?;
+            let index = inner[..end]
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid array index '{}' in key '{key}'", &inner[..end]))?;
+            segments.push(Segment::Index(index));
+            rest = &inner[end + 1..];
+        }
+        if !rest.is_empty() {
+            return Err(format!("Unexpected '{rest}' after ']' in key '{key}'"));
+        }
+    }
+    Ok(segments)
+}
+
+// A numeric segment only makes an array when it continues one, so `ports.8080` stays an object
+// key instead of becoming 8080 nulls
+pub fn unflatten(value: &Value, separator: &str, brackets: bool) -> Result<Value, String> {
+    let flat = match value {
+        Value::Object(flat) => flat,
+        _ => return Err("Only an object of flat keys can be unflattened".to_string()),
+    };
+    let mut entries = vec![];
+    for (key, value) in flat {
+        entries.push((split_key(key, separator, brackets)?, key, value));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut root = Value::Null;
+    // Values set so far, since a null value can't be told apart from a missing one
+    let mut leaves: HashSet<Vec<String>> = HashSet::new();
+    for (segments, key, value) in entries {
+        let conflict = || Err(format!("Key '{key}' conflicts with another key"));
+        let mut path = vec![];
+        let mut node = &mut root;
+        for segment in segments {
+            if leaves.contains(&path) {
+                return conflict();
+            }
+            path.push(segment.name());
+            if node.is_null() {
+                *node = match segment {
+                    Segment::Index(0) => Value::Array(vec![]),
+                    _ => Value::Object(Map::new()),
+                };
+            }
+            node = match (node, segment) {
+                (Value::Array(items), Segment::Index(index)) => {
+                    if index > items.len() {
+                        return Err(format!(
+                            "Key '{key}' skips array indexes, the next one is {}",
+                            items.len()
+                        ));
+                    }
+                    if index == items.len() {
+                        items.push(Value::Null);
+                    }
+                    &mut items[index]
+                }
+                (Value::Object(object), segment) => {
+                    object.entry(segment.name()).or_insert(Value::Null)
+                }
+                _ => return conflict(),
+            };
+        }
+        if !node.is_null() || !leaves.insert(path) {
+            return conflict();
+        }
+        *node = value.clone();
+    }
+    if root.is_null() && leaves.is_empty() {
+        root = Value::Object(Map::new());
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flatten, unflatten};
+    use serde_json::json;
+
+    #[test]
+    fn round_trip() {
+        let value = json!({
+            "a": {"b": [1, {"c": null}, []], "d": {}},
+            "list": (0..12).collect::<Vec<_>>(),
+            "ports": {"8080": "http"},
+        });
+        for (separator, brackets) in [(".", false), ("/", false), (".", true)] {
+            let flat = flatten(&value, separator, brackets);
+            assert_eq!(unflatten(&flat, separator, brackets).unwrap(), value);
+        }
+        assert_eq!(
+            flatten(&value, ".", true)["a.b[1].c"],
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn numeric_keys_only_make_contiguous_arrays() {
+        assert_eq!(
+            unflatten(&json!({"user.4000000000": "x"}), ".", false).unwrap(),
+            json!({"user": {"4000000000": "x"}})
+        );
+        assert_eq!(
+            unflatten(&json!({"a.1": "b", "a.0": "a"}), ".", false).unwrap(),
+            json!({"a": ["a", "b"]})
+        );
+        assert!(unflatten(&json!({"a.0": 1, "a.2": 3}), ".", false).is_err());
+        assert_eq!(
+            unflatten(&json!({"a[5]": 1}), ".", true).unwrap(),
+            json!({"a": {"5": 1}})
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        assert!(unflatten(&json!({"a": null, "a.b": 1}), ".", false).is_err());
+        assert!(unflatten(&json!({"a": 1, "a.b": 1}), ".", false).is_err());
+        assert!(unflatten(&json!({"a.0": 1, "a.x": 1}), ".", false).is_err());
+        assert!(unflatten(&json!({"a[0": 1}), ".", true).is_err());
+        assert!(unflatten(&json!([1]), ".", false).is_err());
+    }
+}
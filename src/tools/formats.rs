@@ -0,0 +1,98 @@
+use super::{env, ini, properties};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Env,
+    Ini,
+    Properties,
+}
+
+pub fn parse_as(input: &str, format: Format) -> Result<Value, String> {
+    match format {
+        Format::Json => serde_json::from_str(input).map_err(|err| err.to_string()),
+        Format::Yaml => serde_yaml_ng::from_str(input).map_err(|err| err.to_string()),
+        Format::Toml => toml::from_str(input).map_err(|err| err.to_string()),
+        Format::Env => env::parse(input),
+        Format::Ini => ini::parse(input),
+        Format::Properties => properties::parse(input),
+    }
+}
+
+// JSON is tried first since it is also valid YAML, and TOML before YAML since
+// YAML would happily read `key = "value"` as a plain string
+pub fn parse_any(input: &str) -> Result<(Value, Format), String> {
+    let mut errors = vec![];
+    for format in [Format::Json, Format::Toml, Format::Yaml] {
+        match parse_as(input, format) {
+            Ok(value) => return Ok((value, format)),
+            Err(err) => errors.push(format!("{format:?}: {err}")),
+        }
+    }
+    Err(format!(
+        "Input is not valid JSON, YAML or TOML\n{}",
+        errors.join("\n")
+    ))
+}
+
+pub fn render(value: &Value, format: Format) -> Result<String, String> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).map_err(|err| err.to_string()),
+        Format::Yaml => serde_yaml_ng::to_string(value)
+            .map(|yaml| yaml.trim_end().to_string())
+            .map_err(|err| err.to_string()),
+        Format::Toml => match value {
+            Value::Object(_) => toml::to_string_pretty(value)
+                .map(|toml| toml.trim_end().to_string())
+                .map_err(|err| err.to_string()),
+            _ => toml::Value::try_from(value)
+                .map(|toml| toml.to_string())
+                .map_err(|err| err.to_string()),
+        },
+        Format::Env => env::emit(value),
+        Format::Ini => ini::emit(value),
+        Format::Properties => properties::emit(value),
+    }
+}
+
+pub fn render_all(values: &[Value], format: Format) -> Result<String, String> {
+    let rendered = values
+        .iter()
+        .map(|value| render(value, format))
+        .collect::<Result<Vec<String>, String>>()?;
+    let separator = match format {
+        Format::Yaml => "\n---\n",
+        _ => "\n",
+    };
+    Ok(rendered.join(separator))
+}
+
+// Picks the format from the file extension, falling back to detecting it from the contents
+pub fn parse_file(path: &str) -> Result<(Value, Format), String> {
+    let input =
+        std::fs::read_to_string(path).map_err(|err| format!("Could not read {path}: {err}"))?;
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    let is_dotenv = std::path::Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == ".env" || name.starts_with(".env."));
+    let format = match extension.as_deref() {
+        _ if is_dotenv => Format::Env,
+        Some("json") => Format::Json,
+        Some("yaml") | Some("yml") => Format::Yaml,
+        Some("toml") => Format::Toml,
+        Some("env") => Format::Env,
+        Some("ini") | Some("cfg") => Format::Ini,
+        Some("properties") => Format::Properties,
+        _ => return parse_any(&input).map_err(|err| format!("{path}: {err}")),
+    };
+    parse_as(&input, format)
+        .map(|value| (value, format))
+        .map_err(|err| format!("{path}: {err}"))
+}
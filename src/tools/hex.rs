@@ -0,0 +1,182 @@
+use super::_text_input;
+use super::binary::{decode_hex, read_raw, write_decoded};
+use crate::{Args, Tools, error, success};
+use console::Term;
+
+pub fn hex_encode(a: &Args, t: &Term) {
+    let msg = "Enter a string to hex encode:";
+    let (data, file, upper, separator, base) = match &a.tool {
+        Some(Tools::HexEncode {
+            data,
+            file,
+            upper,
+            separator,
+            base,
+        }) => (
+            data.clone(),
+            file.clone(),
+            *upper,
+            separator.clone(),
+            base.clone(),
+        ),
+        _ => (None, None, false, None, "hex".to_string()),
+    };
+    match read_raw(t, msg, data, file) {
+        Ok(bytes) => {
+            let result = encode(&bytes, &base, upper, separator.as_deref());
+            success(t, result.as_str(), None, a.plain);
+        }
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn hex_decode(a: &Args, t: &Term) {
+    let msg = "Enter some hex to decode:";
+    let (data, base, hex, out) = match &a.tool {
+        Some(Tools::HexDecode {
+            data,
+            base,
+            hex,
+            out,
+        }) => (data.clone(), base.clone(), *hex, out.clone()),
+        _ => (None, "hex".to_string(), false, None),
+    };
+    let input = _text_input(t, msg, data);
+    let result = decode(&input, &base).and_then(|bytes| write_decoded(t, a.plain, bytes, hex, out));
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+pub fn hexdump(a: &Args, t: &Term) {
+    let msg = "Enter a string to dump:";
+    let (data, file, width) = match &a.tool {
+        Some(Tools::Hexdump { data, file, width }) => (data.clone(), file.clone(), *width),
+        _ => (None, None, 16),
+    };
+    match read_raw(t, msg, data, file) {
+        Ok(bytes) => success(t, dump(&bytes, width.max(1)).as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+// Octal and binary bytes are unreadable when run together so they get a space by default
+fn encode(bytes: &[u8], base: &str, upper: bool, separator: Option<&str>) -> String {
+    let digits: Vec<String> = bytes
+        .iter()
+        .map(|b| match (base, upper) {
+            ("octal", _) => format!("{b:03o}"),
+            ("binary", _) => format!("{b:08b}"),
+            (_, true) => format!("{b:02X}"),
+            (_, false) => format!("{b:02x}"),
+        })
+        .collect();
+    let separator = separator.unwrap_or(if base == "hex" { "" } else { " " });
+    digits.join(separator)
+}
+
+fn decode(input: &str, base: &str) -> Result<Vec<u8>, String> {
+    let (radix, width, prefix) = match base {
+        "octal" => (8, 3, "0o"),
+        "binary" => (2, 8, "0b"),
+        _ => return decode_hex(input),
+    };
+    let mut bytes = vec![];
+    let parts = input
+        .split(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-'))
+        .map(|part| part.strip_prefix(prefix).unwrap_or(part))
+        .filter(|part| !part.is_empty());
+    for part in parts {
+        if let Some(c) = part.chars().find(|c| !c.is_digit(radix)) {
+            return Err(format!("'{c}' is not a valid {base} digit"));
+        }
+        // Digits without separators are read in fixed width chunks
+        let chunks: Vec<&str> = if part.len() <= width {
+            vec![part]
+        } else if part.len().is_multiple_of(width) {
+            (0..part.len())
+                .step_by(width)
+                .map(|i| &part[i..i + width])
+                .collect()
+        } else {
+            return Err(format!(
+                "'{part}' can't be split into {width} digit {base} bytes"
+            ));
+        };
+        for chunk in chunks {
+            let byte = u8::from_str_radix(chunk, radix)
+                .map_err(|_| format!("'{chunk}' is not a valid {base} byte"))?;
+            bytes.push(byte);
+        }
+    }
+    Ok(bytes)
+}
+
+// The same layout as `hexdump -C`: offset, bytes in groups of eight, then the printable ASCII
+fn dump(bytes: &[u8], width: usize) -> String {
+    let mut lines = vec![];
+    for (row, chunk) in bytes.chunks(width).enumerate() {
+        let mut hex = String::new();
+        for i in 0..width {
+            if i > 0 && i.is_multiple_of(8) {
+                hex.push(' ');
+            }
+            match chunk.get(i) {
+                Some(b) => hex.push_str(&format!("{b:02x} ")),
+                None => hex.push_str("   "),
+            }
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    *b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        lines.push(format!("{:08x}  {hex} |{ascii}|", row * width));
+    }
+    lines.push(format!("{:08x}", bytes.len()));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, dump, encode};
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for base in ["hex", "octal", "binary"] {
+            for separator in [None, Some(":"), Some("")] {
+                let encoded = encode(&bytes, base, true, separator);
+                assert_eq!(
+                    decode(&encoded, base).unwrap(),
+                    bytes,
+                    "{base} {separator:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_digits() {
+        assert!(decode("12é45", "octal").is_err());
+        assert!(decode("é", "binary").is_err());
+        assert!(decode("777", "octal").is_err());
+        assert!(decode("0101", "binary").is_ok());
+        assert!(decode("01010", "binary").is_ok());
+        assert!(decode("010101010", "binary").is_err());
+        assert!(decode("aé", "hex").is_err());
+    }
+
+    #[test]
+    fn hexdump_layout() {
+        assert_eq!(
+            dump(b"Hello, world!\n", 16),
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|\n0000000e"
+        );
+    }
+}
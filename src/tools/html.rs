@@ -0,0 +1,137 @@
+use super::_text_input;
+use super::percent::_url_encode;
+use crate::{Args, Tools, success};
+use console::Term;
+use htmlize::{Context, ENTITIES, unescape_in};
+use std::collections::HashMap;
+
+pub fn html_unescape(a: &Args, t: &Term) {
+    let msg = "Enter a HTML escaped string to unescape:";
+    let (data, attribute) = match &a.tool {
+        Some(Tools::HTMLUnescape { data, attribute }) => (data.clone(), *attribute),
+        _ => (None, false),
+    };
+    let input = _text_input(t, msg, data);
+
+    // Malformed entities are handled the way browsers do, by leaving them as they are
+    let context = match attribute {
+        true => Context::Attribute,
+        false => Context::General,
+    };
+    success(t, &unescape_in(input.as_str(), context), None, a.plain);
+}
+
+pub fn html_escape(a: &Args, t: &Term) {
+    let msg = "Enter a string to HTML escape:";
+    let (data, context, named, numeric) = match &a.tool {
+        Some(Tools::HTMLEscape {
+            data,
+            context,
+            named,
+            numeric,
+        }) => (data.clone(), context.clone(), *named, *numeric),
+        _ => (None, "attribute".to_string(), false, false),
+    };
+    let input = _text_input(t, msg, data);
+
+    let result = match context.as_str() {
+        "script" => escape_script(&input),
+        "style" => escape_style(&input),
+        "url" => _url_encode(&input, "component"),
+        context => escape_entities(&input, context == "attribute", named, numeric),
+    };
+    success(t, result.as_str(), None, a.plain);
+}
+
+// Only the characters that matter in the context are escaped by default. --named and --numeric
+// also escape everything outside ASCII, so the result survives any encoding
+fn escape_entities(input: &str, attribute: bool, named: bool, numeric: bool) -> String {
+    let names = if named {
+        entity_names()
+    } else {
+        HashMap::new()
+    };
+    let mut escaped = String::new();
+    for c in input.chars() {
+        let special = matches!(c, '&' | '<' | '>') || (attribute && matches!(c, '"' | '\''));
+        if !special && (c.is_ascii() || !(named || numeric)) {
+            escaped.push(c);
+            continue;
+        }
+        let name = match (c, named) {
+            _ if numeric => None,
+            ('&', _) => Some("amp"),
+            ('<', _) => Some("lt"),
+            ('>', _) => Some("gt"),
+            ('"', _) => Some("quot"),
+            ('\'', true) => Some("apos"),
+            (c, true) => names.get(c.encode_utf8(&mut [0; 4]) as &str).copied(),
+            _ => None,
+        };
+        match name {
+            Some(name) => escaped.push_str(&format!("&{name};")),
+            None => escaped.push_str(&format!("&#x{:X};", c as u32)),
+        }
+    }
+    escaped
+}
+
+// Several names can stand for the same character, e.g. &rarr; and &RightArrow;, so the shortest
+// one is used and lowercase wins a tie
+fn entity_names() -> HashMap<&'static str, &'static str> {
+    let mut names: HashMap<&str, &str> = HashMap::new();
+    for (name, expansion) in ENTITIES.entries() {
+        let (Ok(name), Ok(expansion)) = (std::str::from_utf8(name), std::str::from_utf8(expansion))
+        else {
+            continue;
+        };
+        let Some(name) = name
+            .strip_prefix('&')
+            .and_then(|name| name.strip_suffix(';'))
+        else {
+            continue;
+        };
+        let rank = |name: &str| {
+            (
+                name.len(),
+                name.chars().any(|c| c.is_uppercase()),
+                name.to_string(),
+            )
+        };
+        if names
+            .get(expansion)
+            .is_none_or(|current| rank(name) < rank(current))
+        {
+            names.insert(expansion, name);
+        }
+    }
+    names
+}
+
+// Inside a <script> string literal, so anything that could end the string or the script element
+// is written as a \x or \u escape
+fn escape_script(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || matches!(c, ' ' | ',' | '.' | '_' | '-') => {
+                c.to_string()
+            }
+            c if c.is_ascii() => format!("\\x{:02X}", c as u32),
+            '\u{2028}' | '\u{2029}' => format!("\\u{:04X}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// Inside a CSS string or value. The space after each escape ends it, so a hex digit that
+// follows isn't read as part of it
+fn escape_style(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || !c.is_ascii() => c.to_string(),
+            c => format!("\\{:X} ", c as u32),
+        })
+        .collect()
+}
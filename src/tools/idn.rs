@@ -0,0 +1,214 @@
+use super::_text_input;
+use super::confusables::skeleton;
+use crate::{Args, Tools, error, success};
+use console::Term;
+use idna::uts46::{AsciiDenyList, DnsLength, Hyphens, Uts46};
+use unicode_script::{Script, UnicodeScript};
+
+// UTS #46 also treats the ideographic and full-width full stops as label separators
+const SEPARATORS: [char; 4] = ['.', '。', '．', '｡'];
+
+pub fn idna_encode(a: &Args, t: &Term) {
+    let msg = "Enter a domain name to convert to punycode:";
+    let data = match &a.tool {
+        Some(Tools::IdnaEncode { data }) => data.clone(),
+        _ => None,
+    };
+    let input = _text_input(t, msg, data);
+    let domain = input.trim();
+    let uts46 = Uts46::new();
+    match uts46.to_ascii(
+        domain.as_bytes(),
+        AsciiDenyList::URL,
+        Hyphens::Allow,
+        DnsLength::Ignore,
+    ) {
+        Ok(ascii) => {
+            success(t, &ascii, None, a.plain);
+            if !a.plain {
+                let (unicode, _) =
+                    uts46.to_unicode(ascii.as_bytes(), AsciiDenyList::URL, Hyphens::Allow);
+                warn(t, &unicode, &ascii);
+            }
+        }
+        Err(_) => report_invalid(t, domain),
+    }
+}
+
+pub fn idna_decode(a: &Args, t: &Term) {
+    let msg = "Enter a punycode domain name like xn--bcher-kva.example to decode:";
+    let data = match &a.tool {
+        Some(Tools::IdnaDecode { data }) => data.clone(),
+        _ => None,
+    };
+    let input = _text_input(t, msg, data);
+    let domain = input.trim();
+    let uts46 = Uts46::new();
+    // Invalid labels come back with U+FFFD in them, so the rest of the name is still shown
+    let (unicode, result) = uts46.to_unicode(domain.as_bytes(), AsciiDenyList::URL, Hyphens::Allow);
+    success(t, &unicode, None, a.plain);
+    if result.is_err() {
+        report_invalid(t, domain);
+    }
+    if !a.plain {
+        let ascii = uts46
+            .to_ascii(
+                domain.as_bytes(),
+                AsciiDenyList::URL,
+                Hyphens::Allow,
+                DnsLength::Ignore,
+            )
+            .map(|ascii| ascii.to_string())
+            .unwrap_or(domain.to_string());
+        warn(t, &unicode, &ascii);
+    }
+}
+
+// The idna crate only says that a name is invalid, so each label is checked on its own to find
+// the ones at fault
+fn report_invalid(t: &Term, domain: &str) {
+    let problems: Vec<String> = domain
+        .split(SEPARATORS)
+        .filter_map(|label| label_problem(label).map(|problem| format!("'{label}' {problem}")))
+        .collect();
+    if problems.is_empty() {
+        error(
+            t,
+            "The labels are valid on their own but not together, e.g. a right-to-left label next to one that breaks the bidi rule",
+            Some("Invalid domain"),
+        );
+    }
+    for problem in problems {
+        error(t, &problem, Some("Invalid label"));
+    }
+}
+
+fn label_problem(label: &str) -> Option<String> {
+    let valid = |text: &str| {
+        Uts46::new()
+            .to_ascii(
+                text.as_bytes(),
+                AsciiDenyList::URL,
+                Hyphens::Allow,
+                DnsLength::Ignore,
+            )
+            .is_ok()
+    };
+    if valid(label) {
+        return None;
+    }
+    if label.to_lowercase().starts_with("xn--") {
+        return Some("isn't valid punycode or decodes to characters that aren't allowed".into());
+    }
+    // Combining marks are only invalid at the start, so they aren't checked one by one
+    if let Some(c) = label
+        .chars()
+        .find(|c| c.script() != Script::Inherited && !valid(&c.to_string()))
+    {
+        return Some(format!(
+            "contains '{c}' (U+{:04X}), which isn't allowed",
+            c as u32
+        ));
+    }
+    if label
+        .chars()
+        .next()
+        .is_some_and(|c| c.script() == Script::Inherited)
+    {
+        return Some("starts with a combining mark".into());
+    }
+    Some("breaks the bidi or joiner rules".into())
+}
+
+fn warn(t: &Term, unicode: &str, ascii: &str) {
+    for label in unicode.split('.') {
+        let scripts = scripts(label);
+        if mixed(&scripts) {
+            let names: Vec<&str> = scripts.iter().map(|script| script.full_name()).collect();
+            error(
+                t,
+                format!("'{label}' mixes {}", names.join(" and ")).as_str(),
+                Some("Warning"),
+            );
+        }
+        let lookalike = skeleton(label);
+        if lookalike != label && lookalike.is_ascii() {
+            error(
+                t,
+                format!("'{label}' can be mistaken for '{lookalike}'").as_str(),
+                Some("Warning"),
+            );
+        }
+    }
+    // Resolvers refuse names over these limits even though URLs allow them
+    let mut labels: Vec<&str> = ascii.split('.').collect();
+    if labels.len() > 1 && labels.last() == Some(&"") {
+        labels.pop();
+    }
+    for label in labels {
+        if label.is_empty() {
+            error(t, "The name has an empty label", Some("Warning"));
+        } else if label.len() > 63 {
+            error(
+                t,
+                format!(
+                    "'{label}' is {} characters long, over the DNS limit of 63",
+                    label.len()
+                )
+                .as_str(),
+                Some("Warning"),
+            );
+        }
+    }
+    if ascii.trim_end_matches('.').len() > 253 {
+        error(
+            t,
+            "The name is over the DNS limit of 253 characters",
+            Some("Warning"),
+        );
+    }
+}
+
+fn scripts(label: &str) -> Vec<Script> {
+    let mut scripts = vec![];
+    for script in label.chars().map(|c| c.script()) {
+        if !matches!(script, Script::Common | Script::Inherited) && !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    scripts
+}
+
+// Chinese, Japanese and Korean are written with a mix of scripts, Latin included, so those
+// combinations are fine as UTS #39 allows
+fn mixed(scripts: &[Script]) -> bool {
+    let allowed = [
+        &[
+            Script::Latin,
+            Script::Han,
+            Script::Hiragana,
+            Script::Katakana,
+        ][..],
+        &[Script::Latin, Script::Han, Script::Hangul],
+        &[Script::Latin, Script::Han, Script::Bopomofo],
+    ];
+    scripts.len() > 1
+        && !allowed
+            .iter()
+            .any(|set| scripts.iter().all(|script| set.contains(script)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mixed, scripts};
+
+    #[test]
+    fn mixed_scripts() {
+        assert!(!mixed(&scripts("example")));
+        assert!(!mixed(&scripts("ソニーsony")));
+        assert!(!mixed(&scripts("東京tokyo")));
+        assert!(!mixed(&scripts("한국abc")));
+        assert!(mixed(&scripts("pаypal")));
+        assert!(mixed(&scripts("abcαβγ")));
+    }
+}
@@ -0,0 +1,306 @@
+use super::formats::{parse_any, parse_file};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use url::Url;
+
+const MAX_ENUM_VALUES: usize = 5;
+
+// Everything seen at one location across all of the samples
+#[derive(Default)]
+pub struct Shape {
+    pub count: usize,
+    pub null: bool,
+    pub boolean: bool,
+    pub integer: bool,
+    pub number: bool,
+    pub string: Option<StringShape>,
+    pub object: Option<ObjectShape>,
+    pub array: Option<Box<Shape>>,
+}
+
+#[derive(Default)]
+pub struct StringShape {
+    pub count: usize,
+    pub values: BTreeSet<String>,
+    pub too_many_values: bool,
+    pub format: Option<&'static str>,
+}
+
+#[derive(Default)]
+pub struct ObjectShape {
+    pub count: usize,
+    pub properties: BTreeMap<String, Shape>,
+}
+
+impl Shape {
+    pub fn from_samples(samples: &[Value]) -> Shape {
+        let mut shape = Shape::default();
+        for sample in samples {
+            shape.add(sample);
+        }
+        shape
+    }
+
+    pub fn add(&mut self, value: &Value) {
+        self.count += 1;
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(n) if n.is_i64() || n.is_u64() => self.integer = true,
+            Value::Number(_) => self.number = true,
+            Value::String(s) => {
+                let first = self.string.is_none();
+                let string = self.string.get_or_insert_with(StringShape::default);
+                let format = detect_format(s);
+                if first {
+                    string.format = format;
+                } else if string.format != format {
+                    string.format = None;
+                }
+                string.count += 1;
+                if !string.too_many_values {
+                    string.values.insert(s.clone());
+                    string.too_many_values = string.values.len() > MAX_ENUM_VALUES;
+                }
+            }
+            Value::Array(items) => {
+                let shape = self.array.get_or_insert_with(Box::default);
+                for item in items {
+                    shape.add(item);
+                }
+            }
+            Value::Object(properties) => {
+                let object = self.object.get_or_insert_with(ObjectShape::default);
+                object.count += 1;
+                for (key, value) in properties {
+                    object.properties.entry(key.clone()).or_default().add(value);
+                }
+            }
+        }
+    }
+
+    pub fn is_required(&self, parent: &ObjectShape) -> bool {
+        self.count == parent.count
+    }
+
+    fn to_schema(&self) -> Value {
+        let mut schema = Map::new();
+        let mut types = vec![];
+        if self.object.is_some() {
+            types.push("object");
+        }
+        if self.array.is_some() {
+            types.push("array");
+        }
+        if self.string.is_some() {
+            types.push("string");
+        }
+        if self.number {
+            types.push("number");
+        } else if self.integer {
+            types.push("integer");
+        }
+        if self.boolean {
+            types.push("boolean");
+        }
+        if self.null {
+            types.push("null");
+        }
+        match types.as_slice() {
+            [] => {}
+            [single] => {
+                schema.insert("type".to_string(), json!(single));
+            }
+            _ => {
+                schema.insert("type".to_string(), json!(types));
+            }
+        }
+
+        if let Some(object) = &self.object {
+            let properties: Map<String, Value> = object
+                .properties
+                .iter()
+                .map(|(key, shape)| (key.clone(), shape.to_schema()))
+                .collect();
+            let required: Vec<&String> = object
+                .properties
+                .iter()
+                .filter(|(_, shape)| shape.is_required(object))
+                .map(|(key, _)| key)
+                .collect();
+            schema.insert("properties".to_string(), Value::Object(properties));
+            if !required.is_empty() {
+                schema.insert("required".to_string(), json!(required));
+            }
+        }
+        if let Some(items) = &self.array
+            && items.count > 0
+        {
+            schema.insert("items".to_string(), items.to_schema());
+        }
+        if let Some(string) = &self.string {
+            if let Some(format) = string.format {
+                schema.insert("format".to_string(), json!(format));
+            } else if string.is_enum() && types == ["string"] {
+                schema.insert("enum".to_string(), json!(string.values));
+            }
+        }
+        Value::Object(schema)
+    }
+}
+
+impl StringShape {
+    // Only treated as an enum when values repeat, otherwise every string in a single sample
+    // would become a one value enum
+    pub fn is_enum(&self) -> bool {
+        !self.too_many_values && self.count > self.values.len()
+    }
+}
+
+pub fn detect_format(s: &str) -> Option<&'static str> {
+    if is_date_time(s) {
+        Some("date-time")
+    } else if is_uuid(s) {
+        Some("uuid")
+    } else if is_email(s) {
+        Some("email")
+    } else if is_uri(s) {
+        Some("uri")
+    } else {
+        None
+    }
+}
+
+// RFC 3339, e.g. 2024-01-31T12:00:00.123Z or 2024-01-31 12:00:00+02:00
+fn is_date_time(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        bytes
+            .get(range)
+            .is_some_and(|b| b.iter().all(u8::is_ascii_digit))
+    };
+    if bytes.len() < 20
+        || !digits(0..4)
+        || bytes[4] != b'-'
+        || !digits(5..7)
+        || bytes[7] != b'-'
+        || !digits(8..10)
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || !digits(11..13)
+        || bytes[13] != b':'
+        || !digits(14..16)
+        || bytes[16] != b':'
+        || !digits(17..19)
+    {
+        return false;
+    }
+    let mut rest = &s[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let end = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if end == 0 {
+            return false;
+        }
+        rest = &fraction[end..];
+    }
+    match rest.as_bytes() {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => [h1, h2, m1, m2].iter().all(|b| b.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_email(s: &str) -> bool {
+    match s.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !local.contains(char::is_whitespace)
+                && !domain.contains('@')
+                && !domain.contains(char::is_whitespace)
+                && domain
+                    .split_once('.')
+                    .is_some_and(|(name, tld)| !name.is_empty() && !tld.is_empty())
+        }
+        None => false,
+    }
+}
+
+fn is_uri(s: &str) -> bool {
+    s.contains("://") && Url::parse(s).is_ok()
+}
+
+pub fn json_schema_infer(a: &Args, t: &Term) {
+    let (files, ndjson) = match &a.tool {
+        Some(Tools::JSONSchemaInfer { files, ndjson }) => (files.clone(), *ndjson),
+        _ => (vec![], false),
+    };
+
+    let samples = if files.is_empty() {
+        print(
+            t,
+            "Enter a JSON or YAML sample (type 'END' on a new line to stop input):",
+        );
+        let input = read_input(t, Some(true), Some("END"));
+        read_samples(&input, ndjson)
+    } else {
+        files
+            .iter()
+            .map(|file| {
+                let lines = ndjson || file.ends_with(".ndjson") || file.ends_with(".jsonl");
+                if lines {
+                    std::fs::read_to_string(file)
+                        .map_err(|err| format!("Could not read {file}: {err}"))
+                        .and_then(|input| read_samples(&input, true))
+                } else {
+                    parse_file(file).map(|(value, _)| vec![value])
+                }
+            })
+            .collect::<Result<Vec<Vec<Value>>, String>>()
+            .map(|samples| samples.concat())
+    };
+
+    match samples.and_then(|samples| _json_schema_infer(&samples)) {
+        Ok(schema) => success(t, schema.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn read_samples(input: &str, ndjson: bool) -> Result<Vec<Value>, String> {
+    if !ndjson {
+        return parse_any(input).map(|(value, _)| vec![value]);
+    }
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|err| format!("Line {}: {err}", index + 1))
+        })
+        .collect()
+}
+
+fn _json_schema_infer(samples: &[Value]) -> Result<String, String> {
+    if samples.is_empty() {
+        return Err("No samples were given".to_string());
+    }
+    let mut schema = match Shape::from_samples(samples).to_schema() {
+        Value::Object(schema) => schema,
+        _ => Map::new(),
+    };
+    schema.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    serde_json::to_string_pretty(&schema).map_err(|err| err.to_string())
+}
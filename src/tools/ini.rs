@@ -0,0 +1,257 @@
+use super::flatten::flatten;
+use serde_json::{Map, Value};
+
+// Keys before the first section are kept at the top level. Lines indented deeper than the key
+// continue its value, as do lines ending in a backslash. Keys may be indented too, like in git
+// config files
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut root = Map::new();
+    let mut section: Option<String> = None;
+    // The key whose value is being read, with the indentation of its line
+    let mut last_key: Option<(String, usize)> = None;
+    let mut continues = false;
+    let mut blank_lines = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        let comment = trimmed.starts_with(';') || trimmed.starts_with('#');
+        let deeper = last_key
+            .as_ref()
+            .is_some_and(|(_, key_indent)| indent > *key_indent);
+        if (continues || (deeper && !trimmed.is_empty() && !comment))
+            && let Some((key, _)) = &last_key
+        {
+            let target = match &section {
+                Some(name) => root
+                    .get_mut(name)
+                    .and_then(Value::as_object_mut)
+                    .ok_or(format!("Line {line_number}: section {name} is not a table"))?,
+                None => &mut root,
+            };
+            if let Some(Value::String(value)) = target.get_mut(key) {
+                let (part, next_continues) = continuation(trimmed);
+                // Empty lines only belong to the value when more of it follows
+                if !continues {
+                    value.push_str(&"\n".repeat(blank_lines + 1));
+                }
+                value.push_str(&part);
+                continues = next_continues;
+                blank_lines = 0;
+                continue;
+            }
+        }
+        continues = false;
+
+        if trimmed.is_empty() && last_key.is_some() {
+            blank_lines += 1;
+            continue;
+        }
+        blank_lines = 0;
+        if trimmed.is_empty() || comment {
+            last_key = None;
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or(format!("Line {line_number}: unclosed section header"))?
+                .trim()
+                .to_string();
+            root.entry(name.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            section = Some(name);
+            last_key = None;
+            continue;
+        }
+
+        let (key, value) = match trimmed.find(['=', ':']) {
+            Some(split) => (trimmed[..split].trim(), trimmed[split + 1..].trim()),
+            None => (trimmed, ""),
+        };
+        let (value, next_continues) = continuation(&strip_comment(value));
+        let value = unquote(&value);
+        let target = match &section {
+            Some(name) => root
+                .get_mut(name)
+                .and_then(Value::as_object_mut)
+                .ok_or(format!("Line {line_number}: section {name} is not a table"))?,
+            None => &mut root,
+        };
+        target.insert(key.to_string(), Value::String(value));
+        last_key = Some((key.to_string(), indent));
+        continues = next_continues;
+    }
+    Ok(Value::Object(root))
+}
+
+fn continuation(value: &str) -> (String, bool) {
+    match value.strip_suffix('\\') {
+        Some(value) => (value.to_string(), true),
+        None => (value.to_string(), false),
+    }
+}
+
+// Inline comments need whitespace before them so values like `a#b` are left alone. In a quoted
+// value only what follows the closing quote can be a comment
+fn strip_comment(value: &str) -> String {
+    if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'')
+        && let Some(end) = closing_quote(&value[1..], quote)
+    {
+        let (quoted, rest) = value.split_at(end + 2);
+        let rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with(';') || rest.starts_with('#') {
+            return quoted.to_string();
+        }
+        return value.to_string();
+    }
+    let comment = value.char_indices().find(|(index, c)| {
+        matches!(c, ';' | '#') && value[..*index].ends_with(char::is_whitespace)
+    });
+    match comment {
+        Some((index, _)) => value[..index].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+fn closing_quote(inner: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in inner.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote == '"' => escaped = true,
+            c if c == quote => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            let inner = &value[1..value.len() - 1];
+            return if quote == '"' {
+                inner.replace("\\\"", "\"").replace("\\\\", "\\")
+            } else {
+                inner.to_string()
+            };
+        }
+    }
+    value.to_string()
+}
+
+// Top level objects become sections and anything nested deeper is flattened with dotted keys
+pub fn emit(value: &Value) -> Result<String, String> {
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return Err("Only an object can be converted to INI".to_string()),
+    };
+    let mut globals = vec![];
+    let mut sections = vec![];
+    for (key, value) in object {
+        match value {
+            Value::Object(section) => {
+                let mut lines = vec![format!("[{key}]")];
+                for (key, value) in section {
+                    lines.extend(entries(key, value)?);
+                }
+                sections.push(lines.join("\n"));
+            }
+            _ => globals.extend(entries(key, value)?),
+        }
+    }
+    let mut blocks = vec![];
+    if !globals.is_empty() {
+        blocks.push(globals.join("\n"));
+    }
+    blocks.extend(sections);
+    Ok(blocks.join("\n\n"))
+}
+
+fn entries(key: &str, value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Object(_) | Value::Array(_) => match flatten(value, ".", false) {
+            Value::Object(flat) => flat
+                .iter()
+                .map(|(child, value)| entry(&format!("{key}.{child}"), value))
+                .collect(),
+            _ => Ok(vec![]),
+        },
+        _ => Ok(vec![entry(key, value)?]),
+    }
+}
+
+fn entry(key: &str, value: &Value) -> Result<String, String> {
+    let value = match value {
+        Value::Null => String::new(),
+        // Multi-line values are written as indented continuation lines, which are read back
+        // trimmed and without a trailing line break
+        Value::String(s)
+            if s.contains('\n')
+                && (s.ends_with('\n') || s.lines().any(|line| line.trim() != line)) =>
+        {
+            return Err(format!(
+                "{key} has line breaks at the end or whitespace around its lines, which INI can't keep"
+            ));
+        }
+        Value::String(s) if s.contains('\n') => s.lines().collect::<Vec<&str>>().join("\n  "),
+        Value::String(s) if s.trim() != s || s.contains([';', '#', '"', '\'']) => {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    Ok(format!("{key} = {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{emit, parse};
+    use serde_json::json;
+
+    #[test]
+    fn quoted_values_and_comments() {
+        let ini = "a = \"q;x\" ; note\nb = 'it # s' # c\nc = \"say \\\"hi\\\"\"\nd = a#b ; c\ne = \"a\" b";
+        assert_eq!(
+            parse(ini).unwrap(),
+            json!({"a": "q;x", "b": "it # s", "c": "say \"hi\"", "d": "a#b", "e": "\"a\" b"})
+        );
+    }
+
+    #[test]
+    fn comment_markers() {
+        assert_eq!(
+            parse("a = x # c ; d\nb = y\t; c\nc = x;y").unwrap(),
+            json!({"a": "x", "b": "y", "c": "x;y"})
+        );
+    }
+
+    #[test]
+    fn indented_keys_and_continuations() {
+        let git = "[user]\n\tname = X\n\temail = Y\n\n\tsigningkey = Z";
+        assert_eq!(
+            parse(git).unwrap(),
+            json!({"user": {"name": "X", "email": "Y", "signingkey": "Z"}})
+        );
+        let nested = "[a]\n  key = one\n    two\n\n    three\n  other = x\n";
+        assert_eq!(
+            parse(nested).unwrap(),
+            json!({"a": {"key": "one\ntwo\n\nthree", "other": "x"}})
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = json!({
+            "name": "x",
+            "server": {"host": "example.com", "port": "80"},
+            "text": {"k": "line1\n\nline3", "first": "\nafter a blank line"},
+        });
+        assert_eq!(parse(&emit(&value).unwrap()).unwrap(), value);
+        assert!(emit(&json!({"k": "line\n"})).is_err());
+        assert!(emit(&json!({"k": "a\n  indented"})).is_err());
+    }
+}
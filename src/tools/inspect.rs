@@ -0,0 +1,240 @@
+use super::binary::read_raw;
+use super::charset;
+use super::confusables::lookalike;
+use crate::{Args, Tools, error, success};
+use console::{Style, Term};
+use unicode_general_category::{GeneralCategory, get_general_category};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn inspect_string(a: &Args, t: &Term) {
+    let msg = "Enter a string to inspect:";
+    let (data, file) = match &a.tool {
+        Some(Tools::InspectString { data, file }) => (data.clone(), file.clone()),
+        _ => (None, None),
+    };
+    let bytes = match read_raw(t, msg, data, file) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error(t, err.as_str(), None);
+            return;
+        }
+    };
+
+    if !a.plain {
+        let count = |f: fn(&str) -> usize| -> usize {
+            bytes.utf8_chunks().map(|chunk| f(chunk.valid())).sum()
+        };
+        let summary = format!(
+            "{} code points, {} graphemes, {} UTF-8 bytes, {} UTF-16 units",
+            count(|text| text.chars().count()),
+            count(|text| text.graphemes(true).count()),
+            bytes.len(),
+            count(|text| text.encode_utf16().count())
+        );
+        success(t, summary.as_str(), Some("Length"), false);
+    }
+    if std::str::from_utf8(&bytes).is_err() {
+        error(
+            t,
+            explain_invalid_utf8(&bytes).as_str(),
+            Some("Invalid UTF-8"),
+        );
+    }
+
+    // Invalid bytes can't be part of a grapheme, so each valid run is split on its own
+    let mut grapheme = 0;
+    let mut flagged = 0;
+    for chunk in bytes.utf8_chunks() {
+        for cluster in chunk.valid().graphemes(true) {
+            grapheme += 1;
+            if !a.plain && cluster.chars().count() > 1 {
+                let label = format!("Grapheme {grapheme}");
+                success(t, &shown(cluster), Some(label.as_str()), false);
+            }
+            for c in cluster.chars() {
+                let note = highlight(c);
+                flagged += note.is_some() as usize;
+                if a.plain {
+                    let row = [
+                        grapheme.to_string(),
+                        format!("U+{:04X}", c as u32),
+                        shown(&c.to_string()),
+                        name(c),
+                        get_general_category(c).abbreviation().to_string(),
+                        utf8(c),
+                        utf16(c),
+                        note.unwrap_or_default(),
+                    ];
+                    success(t, row.join("\t").as_str(), None, true);
+                    continue;
+                }
+                let mut line = format!(
+                    "{} {} ({}), UTF-8 {}, UTF-16 {}",
+                    shown(&c.to_string()),
+                    name(c),
+                    get_general_category(c).abbreviation(),
+                    utf8(c),
+                    utf16(c)
+                );
+                if let Some(note) = note {
+                    line.push_str(&format!(" {}", Style::new().yellow().apply_to(note)));
+                }
+                let label = match cluster.chars().count() {
+                    1 => format!("U+{:04X}", c as u32),
+                    _ => format!("  U+{:04X}", c as u32),
+                };
+                success(t, line.as_str(), Some(label.as_str()), false);
+            }
+        }
+        if !chunk.invalid().is_empty() {
+            let hex: Vec<String> = chunk
+                .invalid()
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect();
+            match a.plain {
+                true => success(
+                    t,
+                    format!("\t\t\t<invalid>\t\t{}", hex.join(" ")).as_str(),
+                    None,
+                    true,
+                ),
+                false => error(t, hex.join(" ").as_str(), Some("Invalid bytes")),
+            }
+        }
+    }
+    if !a.plain && flagged > 0 {
+        let summary = format!("{flagged} characters that may not be what they seem");
+        error(t, summary.as_str(), Some("Warning"));
+    }
+}
+
+// Names the first problem in words, since a byte offset alone doesn't say much
+pub fn explain_invalid_utf8(bytes: &[u8]) -> String {
+    let Err(err) = std::str::from_utf8(bytes) else {
+        return "The bytes are valid UTF-8".to_string();
+    };
+    let offset = err.valid_up_to();
+    let byte = bytes[offset];
+    let length = match byte {
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    };
+    let reason = match (byte, err.error_len()) {
+        (0x80..=0xbf, _) => "is a continuation byte without a lead byte before it".to_string(),
+        (0xc0 | 0xc1 | 0xf5..=0xff, _) => "can never appear in UTF-8".to_string(),
+        (_, None) => {
+            format!("starts a {length} byte sequence but the data ends before it's complete")
+        }
+        (_, Some(_)) => match bytes[offset + 1..]
+            .iter()
+            .take(length - 1)
+            .find(|next| !(0x80..=0xbf).contains(*next))
+        {
+            Some(next) => format!(
+                "starts a {length} byte sequence but is followed by 0x{next:02X}, which isn't a continuation byte"
+            ),
+            None => "starts an overlong sequence or a UTF-16 surrogate, which UTF-8 doesn't allow"
+                .to_string(),
+        },
+    };
+    let invalid: usize = bytes.utf8_chunks().map(|chunk| chunk.invalid().len()).sum();
+    let mut explanation = format!("Byte 0x{byte:02X} at offset {offset} {reason}");
+    if invalid > 1 {
+        explanation.push_str(&format!(" ({invalid} invalid bytes in total)"));
+    }
+    // Text that isn't UTF-8 is most often in the old Windows Western European encoding
+    if bytes.len() <= 200
+        && !bytes.contains(&0)
+        && let Ok(text) = charset::decode(bytes, "windows-1252")
+        && !text.chars().any(|c| c.is_control() && !c.is_whitespace())
+    {
+        explanation.push_str(&format!(". As Windows-1252 it reads \"{text}\""));
+    }
+    explanation
+}
+
+fn highlight(c: char) -> Option<String> {
+    let note = match c {
+        '\u{200b}'
+        | '\u{200c}'
+        | '\u{200d}'
+        | '\u{2060}'
+        | '\u{feff}'
+        | '\u{180e}'
+        | '\u{034f}'
+        | '\u{2061}'..='\u{2064}'
+        | '\u{ad}' => "zero-width".to_string(),
+        '\u{061c}'
+        | '\u{200e}'
+        | '\u{200f}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2066}'..='\u{2069}' => "bidi control".to_string(),
+        '\u{a0}' | '\u{2007}' | '\u{202f}' | '\u{2011}' => "non-breaking".to_string(),
+        '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{205f}' | '\u{3000}' => {
+            "unusual space".to_string()
+        }
+        '\u{fe00}'..='\u{fe0f}' | '\u{e0100}'..='\u{e01ef}' => "variation selector".to_string(),
+        '\u{e0000}'..='\u{e007f}' => "invisible tag".to_string(),
+        '\u{fffd}' => "replacement character, left by an earlier decoding error".to_string(),
+        '\t' | '\n' | '\r' => return None,
+        c if c.is_control() => "control character".to_string(),
+        c => format!("looks like '{}'", lookalike(c)?),
+    };
+    Some(format!("[{note}]"))
+}
+
+fn name(c: char) -> String {
+    if let Some(name) = unicode_names2::name(c) {
+        return name.to_string();
+    }
+    // Control characters only have aliases, so the common ones are named here
+    let name = match c {
+        '\0' => "NULL",
+        '\t' => "CHARACTER TABULATION",
+        '\n' => "LINE FEED",
+        '\r' => "CARRIAGE RETURN",
+        '\x1b' => "ESCAPE",
+        '\x7f' => "DELETE",
+        c => match get_general_category(c) {
+            GeneralCategory::Control => "<control>",
+            GeneralCategory::PrivateUse => "<private use>",
+            GeneralCategory::Surrogate => "<surrogate>",
+            _ => "<unassigned>",
+        },
+    };
+    name.to_string()
+}
+
+// Invisible characters are shown as their escape and combining marks on a dotted circle
+fn shown(text: &str) -> String {
+    text.chars()
+        .map(|c| match get_general_category(c) {
+            _ if c.is_control() || highlight(c).is_some_and(|note| !note.starts_with("[looks")) => {
+                format!("\\u{{{:x}}}", c as u32)
+            }
+            GeneralCategory::NonspacingMark | GeneralCategory::EnclosingMark => format!("◌{c}"),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn utf8(c: char) -> String {
+    let mut buffer = [0; 4];
+    let bytes: Vec<String> = c
+        .encode_utf8(&mut buffer)
+        .bytes()
+        .map(|byte| format!("{byte:02X}"))
+        .collect();
+    bytes.join(" ")
+}
+
+fn utf16(c: char) -> String {
+    let units: Vec<String> = c
+        .encode_utf16(&mut [0; 2])
+        .iter()
+        .map(|unit| format!("{unit:04X}"))
+        .collect();
+    units.join(" ")
+}
@@ -0,0 +1,1338 @@
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Str(String),
+    Num(Number),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 23] = [
+    "//", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "|", ",", ":", ";", "?", "(",
+    ")", "[", "]", "{", "}",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '.' {
+            if chars.get(i + 1) == Some(&'.') {
+                tokens.push(Token::DotDot);
+                i += 2;
+            } else if chars.get(i + 1).is_some_and(|n| is_ident_start(*n)) {
+                let start = i + 1;
+                i += 1;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Field(chars[start..i].iter().collect()));
+            } else {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+        } else if c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("Unterminated string literal".to_string());
+            }
+            i += 1;
+            let raw: String = chars[start..i].iter().collect();
+            let value: String = serde_json::from_str(&raw)
+                .map_err(|err| format!("Invalid string literal {raw}: {err}"))?;
+            tokens.push(Token::Str(value));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || chars[i] == 'e'
+                    || chars[i] == 'E'
+                    || ((chars[i] == '-' || chars[i] == '+') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let raw: String = chars[start..i].iter().collect();
+            // Integers are kept exact, so IDs beyond 2^53 survive being compared or printed
+            let num = match (raw.parse::<u64>(), raw.parse::<f64>()) {
+                (Ok(n), _) => Number::from(n),
+                (_, Ok(n)) => Number::from_f64(n).ok_or(format!("Invalid number {raw}"))?,
+                _ => return Err(format!("Invalid number {raw}")),
+            };
+            tokens.push(Token::Num(num));
+        } else if is_ident_start(c) {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if let Some(op) = OPERATORS.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(offset, oc)| chars.get(i + offset) == Some(&oc))
+        }) {
+            tokens.push(Token::Op(op));
+            i += op.len();
+        } else {
+            return Err(format!("Unexpected character '{c}' in filter"));
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Identity,
+    RecurseAll,
+    Literal(Value),
+    Index(Box<Expr>, Box<Expr>),
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
+    Iterate(Box<Expr>),
+    Optional(Box<Expr>),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Expr, Expr)>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Alternative(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
+    Call(String, Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Token::Op(o)) if *o == op)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(i)) if i == keyword)
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Op(o)) if o == op => Ok(()),
+            Some(token) => Err(format!("Expected '{op}' but found {token:?}")),
+            None => Err(format!("Expected '{op}' but reached the end of the filter")),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Ident(i)) if i == keyword => Ok(()),
+            Some(token) => Err(format!("Expected '{keyword}' but found {token:?}")),
+            None => Err(format!(
+                "Expected '{keyword}' but reached the end of the filter"
+            )),
+        }
+    }
+
+    fn parse_pipe(&mut self) -> Result<Expr, String> {
+        let left = self.parse_comma()?;
+        if self.is_op("|") {
+            self.next();
+            let right = self.parse_pipe()?;
+            return Ok(Expr::Pipe(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_comma(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_alternative()?;
+        while self.is_op(",") {
+            self.next();
+            let right = self.parse_alternative()?;
+            left = Expr::Comma(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_alternative(&mut self) -> Result<Expr, String> {
+        let left = self.parse_or()?;
+        if self.is_op("//") {
+            self.next();
+            let right = self.parse_alternative()?;
+            return Ok(Expr::Alternative(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.next();
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_comparison()?;
+        while self.is_keyword("and") {
+            self.next();
+            let right = self.parse_comparison()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let left = self.parse_additive()?;
+        for op in ["==", "!=", "<=", ">=", "<", ">"] {
+            if self.is_op(op) {
+                self.next();
+                let right = self.parse_additive()?;
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(right)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op @ ("+" | "-"))) => *op,
+                _ => return Ok(left),
+            };
+            self.next();
+            let right = self.parse_multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op @ ("*" | "/" | "%"))) => *op,
+                _ => return Ok(left),
+            };
+            self.next();
+            let right = self.parse_unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.is_op("-") {
+            self.next();
+            return Ok(Expr::Negate(Box::new(self.parse_postfix()?)));
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = name.clone();
+                    self.next();
+                    expr =
+                        Expr::Index(Box::new(expr), Box::new(Expr::Literal(Value::String(name))));
+                }
+                Some(Token::Dot) => match self.tokens.get(self.pos + 1) {
+                    Some(Token::Str(name)) => {
+                        let name = name.clone();
+                        self.pos += 2;
+                        expr = Expr::Index(
+                            Box::new(expr),
+                            Box::new(Expr::Literal(Value::String(name))),
+                        );
+                    }
+                    Some(Token::Op("[")) => {
+                        self.next();
+                    }
+                    _ => return Ok(expr),
+                },
+                Some(Token::Op("[")) => {
+                    self.next();
+                    expr = self.parse_brackets(expr)?;
+                }
+                Some(Token::Op("?")) => {
+                    self.next();
+                    expr = Expr::Optional(Box::new(expr));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    // Called after the opening bracket of `[]`, `[index]` or `[from:to]`
+    fn parse_brackets(&mut self, base: Expr) -> Result<Expr, String> {
+        if self.is_op("]") {
+            self.next();
+            return Ok(Expr::Iterate(Box::new(base)));
+        }
+        let from = if self.is_op(":") {
+            None
+        } else {
+            Some(Box::new(self.parse_pipe()?))
+        };
+        if self.is_op(":") {
+            self.next();
+            let to = if self.is_op("]") {
+                None
+            } else {
+                Some(Box::new(self.parse_pipe()?))
+            };
+            self.expect_op("]")?;
+            return Ok(Expr::Slice(Box::new(base), from, to));
+        }
+        self.expect_op("]")?;
+        match from {
+            Some(index) => Ok(Expr::Index(Box::new(base), index)),
+            None => Err("Expected an index inside '[]'".to_string()),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Dot) => match self.peek() {
+                Some(Token::Str(name)) => {
+                    let name = name.clone();
+                    self.next();
+                    Ok(Expr::Index(
+                        Box::new(Expr::Identity),
+                        Box::new(Expr::Literal(Value::String(name))),
+                    ))
+                }
+                _ => Ok(Expr::Identity),
+            },
+            Some(Token::DotDot) => Ok(Expr::RecurseAll),
+            Some(Token::Field(name)) => Ok(Expr::Index(
+                Box::new(Expr::Identity),
+                Box::new(Expr::Literal(Value::String(name))),
+            )),
+            Some(Token::Str(value)) => Ok(Expr::Literal(Value::String(value))),
+            Some(Token::Num(value)) => Ok(Expr::Literal(Value::Number(value))),
+            Some(Token::Op("(")) => {
+                let expr = self.parse_pipe()?;
+                self.expect_op(")")?;
+                Ok(expr)
+            }
+            Some(Token::Op("[")) => {
+                if self.is_op("]") {
+                    self.next();
+                    return Ok(Expr::Array(None));
+                }
+                let expr = self.parse_pipe()?;
+                self.expect_op("]")?;
+                Ok(Expr::Array(Some(Box::new(expr))))
+            }
+            Some(Token::Op("{")) => self.parse_object(),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "if" => self.parse_if(),
+                _ => {
+                    let mut args = vec![];
+                    if self.is_op("(") {
+                        self.next();
+                        loop {
+                            args.push(self.parse_pipe()?);
+                            if self.is_op(";") {
+                                self.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        self.expect_op(")")?;
+                    }
+                    Ok(Expr::Call(name, args))
+                }
+            },
+            Some(token) => Err(format!("Unexpected {token:?} in filter")),
+            None => Err("Unexpected end of filter".to_string()),
+        }
+    }
+
+    // Called after the `if` keyword
+    fn parse_if(&mut self) -> Result<Expr, String> {
+        let condition = self.parse_pipe()?;
+        self.expect_keyword("then")?;
+        let then = self.parse_pipe()?;
+        let otherwise = if self.is_keyword("elif") {
+            self.next();
+            Some(Box::new(self.parse_if()?))
+        } else if self.is_keyword("else") {
+            self.next();
+            let otherwise = self.parse_pipe()?;
+            self.expect_keyword("end")?;
+            Some(Box::new(otherwise))
+        } else {
+            self.expect_keyword("end")?;
+            None
+        };
+        Ok(Expr::If(Box::new(condition), Box::new(then), otherwise))
+    }
+
+    // Called after the opening brace
+    fn parse_object(&mut self) -> Result<Expr, String> {
+        let mut entries = vec![];
+        while !self.is_op("}") {
+            let key = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => {
+                    Expr::Literal(Value::String(name))
+                }
+                Some(Token::Op("(")) => {
+                    let key = self.parse_pipe()?;
+                    self.expect_op(")")?;
+                    key
+                }
+                Some(token) => return Err(format!("Unexpected {token:?} in object key")),
+                None => return Err("Unterminated object construction".to_string()),
+            };
+            let value = if self.is_op(":") {
+                self.next();
+                self.parse_alternative()?
+            } else {
+                Expr::Index(Box::new(Expr::Identity), Box::new(key.clone()))
+            };
+            entries.push((key, value));
+            if self.is_op(",") {
+                self.next();
+            } else {
+                break;
+            }
+        }
+        self.expect_op("}")?;
+        Ok(Expr::Object(entries))
+    }
+}
+
+pub fn run(filter: &str, input: &Value) -> Result<Vec<Value>, String> {
+    let mut parser = Parser {
+        tokens: tokenize(filter)?,
+        pos: 0,
+    };
+    let expr = parser.parse_pipe()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("Unexpected {token:?} in filter"));
+    }
+    eval(&expr, input)
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, String> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::RecurseAll => {
+            let mut out = vec![];
+            recurse(input, &mut out);
+            Ok(out)
+        }
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Index(base, index) => {
+            let mut out = vec![];
+            for value in eval(base, input)? {
+                for key in eval(index, input)? {
+                    out.push(index_value(&value, &key)?);
+                }
+            }
+            Ok(out)
+        }
+        Expr::Slice(base, from, to) => {
+            let from = match from {
+                Some(from) => eval(from, input)?,
+                None => vec![Value::Null],
+            };
+            let to = match to {
+                Some(to) => eval(to, input)?,
+                None => vec![Value::Null],
+            };
+            let mut out = vec![];
+            for value in eval(base, input)? {
+                for start in &from {
+                    for end in &to {
+                        out.push(slice_value(&value, start, end)?);
+                    }
+                }
+            }
+            Ok(out)
+        }
+        Expr::Iterate(base) => {
+            let mut out = vec![];
+            for value in eval(base, input)? {
+                out.extend(iterate(&value)?);
+            }
+            Ok(out)
+        }
+        Expr::Optional(inner) => Ok(eval(inner, input).unwrap_or_default()),
+        Expr::Array(inner) => match inner {
+            Some(inner) => Ok(vec![Value::Array(eval(inner, input)?)]),
+            None => Ok(vec![Value::Array(vec![])]),
+        },
+        Expr::Object(entries) => {
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let keys = eval(key, input)?;
+                let values = eval(value, input)?;
+                let mut next = vec![];
+                for object in &objects {
+                    for key in &keys {
+                        let key = match key {
+                            Value::String(key) => key.clone(),
+                            other => {
+                                return Err(format!(
+                                    "Object keys must be strings, not {}",
+                                    type_name(other)
+                                ));
+                            }
+                        };
+                        for value in &values {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), value.clone());
+                            next.push(object);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            Ok(objects.into_iter().map(Value::Object).collect())
+        }
+        Expr::Pipe(left, right) => {
+            let mut out = vec![];
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            Ok(out)
+        }
+        Expr::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            Ok(out)
+        }
+        Expr::Alternative(left, right) => {
+            let truthy: Vec<Value> = eval(left, input)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(is_truthy)
+                .collect();
+            if truthy.is_empty() {
+                eval(right, input)
+            } else {
+                Ok(truthy)
+            }
+        }
+        Expr::And(left, right) => {
+            let mut out = vec![];
+            for l in eval(left, input)? {
+                if !is_truthy(&l) {
+                    out.push(Value::Bool(false));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Value::Bool(is_truthy(&r)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Or(left, right) => {
+            let mut out = vec![];
+            for l in eval(left, input)? {
+                if is_truthy(&l) {
+                    out.push(Value::Bool(true));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Value::Bool(is_truthy(&r)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Negate(inner) => eval(inner, input)?
+            .iter()
+            .map(|value| match value {
+                Value::Number(n) => Ok(arithmetic(
+                    &Number::from(0),
+                    n,
+                    i128::checked_sub,
+                    |a, b| a - b,
+                )),
+                _ => Err(format!("{} cannot be negated", type_name(value))),
+            })
+            .collect(),
+        Expr::Binary(op, left, right) => {
+            let lefts = eval(left, input)?;
+            let mut out = vec![];
+            for r in eval(right, input)? {
+                for l in &lefts {
+                    out.push(binary(op, l, &r)?);
+                }
+            }
+            Ok(out)
+        }
+        Expr::If(condition, then, otherwise) => {
+            let mut out = vec![];
+            for c in eval(condition, input)? {
+                if is_truthy(&c) {
+                    out.extend(eval(then, input)?);
+                } else {
+                    match otherwise {
+                        Some(otherwise) => out.extend(eval(otherwise, input)?),
+                        None => out.push(input.clone()),
+                    }
+                }
+            }
+            Ok(out)
+        }
+        Expr::Call(name, args) => call(name, args, input),
+    }
+}
+
+fn call(name: &str, args: &[Expr], input: &Value) -> Result<Vec<Value>, String> {
+    let one = |value: Value| Ok(vec![value]);
+    match (name, args) {
+        ("empty", []) => Ok(vec![]),
+        ("error", []) => Err(as_message(input)),
+        ("error", [message]) => Err(eval(message, input)?
+            .first()
+            .map(as_message)
+            .unwrap_or_default()),
+        ("not", []) => one(Value::Bool(!is_truthy(input))),
+        ("length", []) => one(match input {
+            Value::Null => number(0.0),
+            Value::Bool(_) => return Err("boolean has no length".to_string()),
+            Value::Number(n) => arithmetic(n, n, |_, b| b.checked_abs(), |_, b| b.abs()),
+            Value::String(s) => number(s.chars().count() as f64),
+            Value::Array(a) => number(a.len() as f64),
+            Value::Object(o) => number(o.len() as f64),
+        }),
+        ("keys", []) => one(match input {
+            Value::Object(o) => {
+                let mut keys: Vec<&String> = o.keys().collect();
+                keys.sort();
+                Value::Array(keys.into_iter().map(|k| Value::String(k.clone())).collect())
+            }
+            Value::Array(a) => Value::Array((0..a.len()).map(|i| number(i as f64)).collect()),
+            other => return Err(format!("{} has no keys", type_name(other))),
+        }),
+        ("has", [key]) => eval(key, input)?
+            .iter()
+            .map(|key| match (input, key) {
+                (Value::Object(o), Value::String(k)) => Ok(Value::Bool(o.contains_key(k))),
+                (Value::Array(a), Value::Number(n)) => Ok(Value::Bool(
+                    n.as_f64()
+                        .is_some_and(|n| n >= 0.0 && (n as usize) < a.len()),
+                )),
+                _ => Err(format!(
+                    "Cannot check whether {} has a {} key",
+                    type_name(input),
+                    type_name(key)
+                )),
+            })
+            .collect(),
+        ("type", []) => one(Value::String(type_name(input).to_string())),
+        ("select", [condition]) => {
+            let mut out = vec![];
+            for c in eval(condition, input)? {
+                if is_truthy(&c) {
+                    out.push(input.clone());
+                }
+            }
+            Ok(out)
+        }
+        ("values", []) => Ok(if input.is_null() {
+            vec![]
+        } else {
+            vec![input.clone()]
+        }),
+        ("map", [f]) => {
+            let mut out = vec![];
+            for value in iterate(input)? {
+                out.extend(eval(f, &value)?);
+            }
+            one(Value::Array(out))
+        }
+        ("map_values", [f]) => match input {
+            Value::Object(o) => {
+                let mut out = Map::new();
+                for (k, v) in o {
+                    if let Some(first) = eval(f, v)?.into_iter().next() {
+                        out.insert(k.clone(), first);
+                    }
+                }
+                one(Value::Object(out))
+            }
+            Value::Array(a) => {
+                let mut out = vec![];
+                for v in a {
+                    if let Some(first) = eval(f, v)?.into_iter().next() {
+                        out.push(first);
+                    }
+                }
+                one(Value::Array(out))
+            }
+            other => Err(format!("Cannot iterate over {}", type_name(other))),
+        },
+        ("recurse", []) => eval(&Expr::RecurseAll, input),
+        ("add", []) => {
+            let mut total = Value::Null;
+            for value in iterate(input)? {
+                total = binary("+", &total, &value)?;
+            }
+            one(total)
+        }
+        ("any", []) => one(Value::Bool(iterate(input)?.iter().any(is_truthy))),
+        ("all", []) => one(Value::Bool(iterate(input)?.iter().all(is_truthy))),
+        ("any", [f]) | ("all", [f]) => {
+            let mut results = vec![];
+            for value in iterate(input)? {
+                results.extend(eval(f, &value)?);
+            }
+            one(Value::Bool(if name == "any" {
+                results.iter().any(is_truthy)
+            } else {
+                results.iter().all(is_truthy)
+            }))
+        }
+        ("first", []) => one(index_value(input, &number(0.0))?),
+        ("last", []) => one(index_value(input, &number(-1.0))?),
+        ("first", [f]) => Ok(eval(f, input)?.into_iter().take(1).collect()),
+        ("last", [f]) => Ok(eval(f, input)?.into_iter().last().into_iter().collect()),
+        ("limit", [n, f]) => {
+            let mut out = vec![];
+            for n in eval(n, input)? {
+                let n = n.as_f64().ok_or("limit requires a number")? as usize;
+                out.extend(eval(f, input)?.into_iter().take(n));
+            }
+            Ok(out)
+        }
+        ("range", [to]) => {
+            let mut out = vec![];
+            for to in eval(to, input)? {
+                let to = to.as_f64().ok_or("range requires numbers")?;
+                let mut i = 0.0;
+                while i < to {
+                    out.push(number(i));
+                    i += 1.0;
+                }
+            }
+            Ok(out)
+        }
+        ("range", [from, to]) => {
+            let mut out = vec![];
+            for from in eval(from, input)? {
+                for to in eval(to, input)? {
+                    let from = from.as_f64().ok_or("range requires numbers")?;
+                    let to = to.as_f64().ok_or("range requires numbers")?;
+                    let mut i = from;
+                    while i < to {
+                        out.push(number(i));
+                        i += 1.0;
+                    }
+                }
+            }
+            Ok(out)
+        }
+        ("reverse", []) => one(match input {
+            Value::Array(a) => Value::Array(a.iter().rev().cloned().collect()),
+            Value::String(s) => Value::String(s.chars().rev().collect()),
+            Value::Null => Value::Array(vec![]),
+            other => return Err(format!("Cannot reverse {}", type_name(other))),
+        }),
+        ("sort", []) => {
+            let mut values = array(input, name)?.clone();
+            values.sort_by(compare);
+            one(Value::Array(values))
+        }
+        ("sort_by", [f]) => {
+            let mut keyed = keyed_by(input, f, name)?;
+            keyed.sort_by(|a, b| compare(&a.0, &b.0));
+            one(Value::Array(keyed.into_iter().map(|(_, v)| v).collect()))
+        }
+        ("group_by", [f]) => {
+            let mut keyed = keyed_by(input, f, name)?;
+            keyed.sort_by(|a, b| compare(&a.0, &b.0));
+            let mut groups: Vec<(Value, Vec<Value>)> = vec![];
+            for (key, value) in keyed {
+                match groups.last_mut() {
+                    Some((last, group)) if *last == key => group.push(value),
+                    _ => groups.push((key, vec![value])),
+                }
+            }
+            one(Value::Array(
+                groups.into_iter().map(|(_, g)| Value::Array(g)).collect(),
+            ))
+        }
+        ("unique", []) => {
+            let mut values = array(input, name)?.clone();
+            values.sort_by(compare);
+            values.dedup();
+            one(Value::Array(values))
+        }
+        ("unique_by", [f]) => {
+            let mut keyed = keyed_by(input, f, name)?;
+            keyed.sort_by(|a, b| compare(&a.0, &b.0));
+            keyed.dedup_by(|a, b| a.0 == b.0);
+            one(Value::Array(keyed.into_iter().map(|(_, v)| v).collect()))
+        }
+        ("min", []) => one(array(input, name)?
+            .iter()
+            .min_by(|a, b| compare(a, b))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        ("max", []) => one(array(input, name)?
+            .iter()
+            .max_by(|a, b| compare(a, b))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        ("min_by", [f]) => one(keyed_by(input, f, name)?
+            .into_iter()
+            .min_by(|a, b| compare(&a.0, &b.0))
+            .map(|(_, v)| v)
+            .unwrap_or(Value::Null)),
+        ("max_by", [f]) => one(keyed_by(input, f, name)?
+            .into_iter()
+            .max_by(|a, b| compare(&a.0, &b.0))
+            .map(|(_, v)| v)
+            .unwrap_or(Value::Null)),
+        ("flatten", []) => one(Value::Array(flatten(array(input, name)?))),
+        ("to_entries", []) => match input {
+            Value::Object(o) => one(Value::Array(
+                o.iter()
+                    .map(|(k, v)| {
+                        let mut entry = Map::new();
+                        entry.insert("key".to_string(), Value::String(k.clone()));
+                        entry.insert("value".to_string(), v.clone());
+                        Value::Object(entry)
+                    })
+                    .collect(),
+            )),
+            other => Err(format!("{} has no keys", type_name(other))),
+        },
+        ("from_entries", []) => {
+            let mut out = Map::new();
+            for entry in array(input, name)? {
+                let key = ["key", "k", "name", "Name", "Key", "K"]
+                    .iter()
+                    .find_map(|k| entry.get(k).filter(|v| !v.is_null()))
+                    .ok_or("from_entries requires objects with a key")?;
+                let key = match key {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let value = ["value", "v", "Value", "V"]
+                    .iter()
+                    .find_map(|k| entry.get(k))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                out.insert(key, value);
+            }
+            one(Value::Object(out))
+        }
+        ("with_entries", [f]) => {
+            let entries = call("to_entries", &[], input)?;
+            let mapped = call("map", std::slice::from_ref(f), &entries[0])?;
+            call("from_entries", &[], &mapped[0])
+        }
+        ("tostring", []) => one(Value::String(as_message(input))),
+        ("tojson", []) => one(Value::String(input.to_string())),
+        ("fromjson", []) => match input {
+            Value::String(s) => one(serde_json::from_str(s).map_err(|err| err.to_string())?),
+            other => Err(format!("{} cannot be parsed as JSON", type_name(other))),
+        },
+        ("tonumber", []) => match input {
+            Value::Number(_) => one(input.clone()),
+            Value::String(s) => one(number(
+                s.trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("Cannot parse '{s}' as a number"))?,
+            )),
+            other => Err(format!("{} cannot be parsed as a number", type_name(other))),
+        },
+        ("ascii_downcase", []) => one(Value::String(string(input, name)?.to_ascii_lowercase())),
+        ("ascii_upcase", []) => one(Value::String(string(input, name)?.to_ascii_uppercase())),
+        ("floor", []) | ("ceil", []) | ("round", []) => {
+            let n = input.as_f64().ok_or(format!("{name} requires a number"))?;
+            one(number(match name {
+                "floor" => n.floor(),
+                "ceil" => n.ceil(),
+                _ => n.round(),
+            }))
+        }
+        ("startswith", [s])
+        | ("endswith", [s])
+        | ("ltrimstr", [s])
+        | ("rtrimstr", [s])
+        | ("split", [s])
+        | ("contains", [s])
+        | ("join", [s]) => {
+            let mut out = vec![];
+            for arg in eval(s, input)? {
+                out.push(string_function(name, input, &arg)?);
+            }
+            Ok(out)
+        }
+        _ => Err(format!("{name}/{} is not defined", args.len())),
+    }
+}
+
+fn string_function(name: &str, input: &Value, arg: &Value) -> Result<Value, String> {
+    if name == "contains" {
+        return Ok(Value::Bool(contains(input, arg)));
+    }
+    if name == "join" {
+        let separator = string(arg, name)?;
+        let parts = array(input, name)?
+            .iter()
+            .map(|v| match v {
+                Value::Null => Ok(String::new()),
+                Value::String(s) => Ok(s.clone()),
+                Value::Number(_) | Value::Bool(_) => Ok(v.to_string()),
+                other => Err(format!("Cannot join {}", type_name(other))),
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+        return Ok(Value::String(parts.join(separator)));
+    }
+    let s = string(input, name)?;
+    let arg = string(arg, name)?;
+    Ok(match name {
+        "startswith" => Value::Bool(s.starts_with(arg)),
+        "endswith" => Value::Bool(s.ends_with(arg)),
+        "ltrimstr" => Value::String(s.strip_prefix(arg).unwrap_or(s).to_string()),
+        "rtrimstr" => Value::String(s.strip_suffix(arg).unwrap_or(s).to_string()),
+        _ => Value::Array(
+            s.split(arg)
+                .map(|part| Value::String(part.to_string()))
+                .collect(),
+        ),
+    })
+}
+
+fn contains(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => b
+            .iter()
+            .all(|(k, bv)| a.get(k).is_some_and(|av| contains(av, bv))),
+        (Value::Array(a), Value::Array(b)) => {
+            b.iter().all(|bv| a.iter().any(|av| contains(av, bv)))
+        }
+        (Value::String(a), Value::String(b)) => a.contains(b.as_str()),
+        _ => a == b,
+    }
+}
+
+fn keyed_by(input: &Value, f: &Expr, name: &str) -> Result<Vec<(Value, Value)>, String> {
+    array(input, name)?
+        .iter()
+        .map(|value| Ok((Value::Array(eval(f, value)?), value.clone())))
+        .collect()
+}
+
+fn flatten(values: &[Value]) -> Vec<Value> {
+    let mut out = vec![];
+    for value in values {
+        match value {
+            Value::Array(inner) => out.extend(flatten(inner)),
+            other => out.push(other.clone()),
+        }
+    }
+    out
+}
+
+fn array<'a>(input: &'a Value, name: &str) -> Result<&'a Vec<Value>, String> {
+    input.as_array().ok_or(format!(
+        "{name} requires an array, not {}",
+        type_name(input)
+    ))
+}
+
+fn string<'a>(input: &'a Value, name: &str) -> Result<&'a str, String> {
+    input.as_str().ok_or(format!(
+        "{name} requires a string, not {}",
+        type_name(input)
+    ))
+}
+
+fn as_message(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn recurse(value: &Value, out: &mut Vec<Value>) {
+    out.push(value.clone());
+    match value {
+        Value::Array(a) => a.iter().for_each(|v| recurse(v, out)),
+        Value::Object(o) => o.values().for_each(|v| recurse(v, out)),
+        _ => {}
+    }
+}
+
+fn iterate(value: &Value) -> Result<Vec<Value>, String> {
+    match value {
+        Value::Array(a) => Ok(a.clone()),
+        Value::Object(o) => Ok(o.values().cloned().collect()),
+        other => Err(format!("Cannot iterate over {}", type_name(other))),
+    }
+}
+
+fn index_value(value: &Value, key: &Value) -> Result<Value, String> {
+    match (value, key) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Object(o), Value::String(k)) => Ok(o.get(k).cloned().unwrap_or(Value::Null)),
+        (Value::Array(a), Value::Number(n)) => {
+            let n = n.as_f64().unwrap_or_default().floor() as i64;
+            let index = if n < 0 { a.len() as i64 + n } else { n };
+            Ok(usize::try_from(index)
+                .ok()
+                .and_then(|i| a.get(i))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        _ => Err(format!(
+            "Cannot index {} with {}",
+            type_name(value),
+            type_name(key)
+        )),
+    }
+}
+
+fn slice_value(value: &Value, from: &Value, to: &Value) -> Result<Value, String> {
+    let bounds = |len: usize| -> Result<(usize, usize), String> {
+        let resolve = |bound: &Value, default: usize| -> Result<usize, String> {
+            match bound {
+                Value::Null => Ok(default),
+                Value::Number(n) => {
+                    let n = n.as_f64().unwrap_or_default().floor() as i64;
+                    let n = if n < 0 { len as i64 + n } else { n };
+                    Ok(n.clamp(0, len as i64) as usize)
+                }
+                other => Err(format!("Cannot slice with {}", type_name(other))),
+            }
+        };
+        let start = resolve(from, 0)?;
+        Ok((start, resolve(to, len)?.max(start)))
+    };
+    match value {
+        Value::Null => Ok(Value::Null),
+        Value::Array(a) => {
+            let (start, end) = bounds(a.len())?;
+            Ok(Value::Array(a[start..end].to_vec()))
+        }
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            let (start, end) = bounds(chars.len())?;
+            Ok(Value::String(chars[start..end].iter().collect()))
+        }
+        other => Err(format!("Cannot slice {}", type_name(other))),
+    }
+}
+
+fn binary(op: &str, l: &Value, r: &Value) -> Result<Value, String> {
+    let invalid = || {
+        Err(format!(
+            "{} and {} cannot be used with '{op}'",
+            type_name(l),
+            type_name(r)
+        ))
+    };
+    match op {
+        "==" => Ok(Value::Bool(compare(l, r) == Ordering::Equal)),
+        "!=" => Ok(Value::Bool(compare(l, r) != Ordering::Equal)),
+        "<" => Ok(Value::Bool(compare(l, r) == Ordering::Less)),
+        "<=" => Ok(Value::Bool(compare(l, r) != Ordering::Greater)),
+        ">" => Ok(Value::Bool(compare(l, r) == Ordering::Greater)),
+        ">=" => Ok(Value::Bool(compare(l, r) != Ordering::Less)),
+        "+" => match (l, r) {
+            (Value::Null, other) | (other, Value::Null) => Ok(other.clone()),
+            (Value::Number(a), Value::Number(b)) => {
+                Ok(arithmetic(a, b, i128::checked_add, |a, b| a + b))
+            }
+            (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}"))),
+            (Value::Array(a), Value::Array(b)) => {
+                Ok(Value::Array(a.iter().chain(b.iter()).cloned().collect()))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                let mut merged = a.clone();
+                merged.extend(b.clone());
+                Ok(Value::Object(merged))
+            }
+            _ => invalid(),
+        },
+        "-" => match (l, r) {
+            (Value::Number(a), Value::Number(b)) => {
+                Ok(arithmetic(a, b, i128::checked_sub, |a, b| a - b))
+            }
+            (Value::Array(a), Value::Array(b)) => Ok(Value::Array(
+                a.iter().filter(|v| !b.contains(v)).cloned().collect(),
+            )),
+            _ => invalid(),
+        },
+        "*" => match (l, r) {
+            (Value::Number(a), Value::Number(b)) => {
+                Ok(arithmetic(a, b, i128::checked_mul, |a, b| a * b))
+            }
+            (Value::Object(_), Value::Object(_)) => Ok(deep_merge(l, r)),
+            _ => invalid(),
+        },
+        "/" => match (l, r) {
+            (Value::Number(a), Value::Number(b)) => {
+                let b = b.as_f64().unwrap_or_default();
+                if b == 0.0 {
+                    return Err("Cannot divide by zero".to_string());
+                }
+                Ok(number(a.as_f64().unwrap_or_default() / b))
+            }
+            (Value::String(_), Value::String(_)) => string_function("split", l, r),
+            _ => invalid(),
+        },
+        "%" => match (l, r) {
+            (Value::Number(a), Value::Number(b)) => {
+                let b = b.as_f64().unwrap_or_default() as i64;
+                if b == 0 {
+                    return Err("Cannot divide by zero".to_string());
+                }
+                Ok(number((a.as_f64().unwrap_or_default() as i64 % b) as f64))
+            }
+            _ => invalid(),
+        },
+        _ => invalid(),
+    }
+}
+
+fn deep_merge(l: &Value, r: &Value) -> Value {
+    match (l, r) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut merged = a.clone();
+            for (k, v) in b {
+                let value = match merged.get(k) {
+                    Some(existing) => deep_merge(existing, v),
+                    None => v.clone(),
+                };
+                merged.insert(k.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        _ => r.clone(),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    }
+}
+
+// Follows jq's ordering: null < false < true < numbers < strings < arrays < objects
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (integer(a), integer(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .unwrap_or_default()
+                .total_cmp(&b.as_f64().unwrap_or_default()),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                let ordering = compare(x, y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a_keys: Vec<&String> = a.keys().collect();
+            let mut b_keys: Vec<&String> = b.keys().collect();
+            a_keys.sort();
+            b_keys.sort();
+            a_keys.cmp(&b_keys).then_with(|| {
+                a_keys
+                    .iter()
+                    .map(|k| compare(&a[*k], &b[*k]))
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::Number(Number::from(n as i64))
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn integer(n: &Number) -> Option<i128> {
+    n.as_i64()
+        .map(i128::from)
+        .or_else(|| n.as_u64().map(i128::from))
+}
+
+// Integer arithmetic stays exact as long as the result fits, and falls back to floats otherwise
+fn arithmetic(
+    a: &Number,
+    b: &Number,
+    exact: fn(i128, i128) -> Option<i128>,
+    float: fn(f64, f64) -> f64,
+) -> Value {
+    let result = integer(a)
+        .zip(integer(b))
+        .and_then(|(a, b)| exact(a, b))
+        .and_then(|n| {
+            i64::try_from(n)
+                .map(Number::from)
+                .or_else(|_| u64::try_from(n).map(Number::from))
+                .ok()
+        });
+    match result {
+        Some(n) => Value::Number(n),
+        None => number(float(
+            a.as_f64().unwrap_or_default(),
+            b.as_f64().unwrap_or_default(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use serde_json::{Value, json};
+
+    fn query(filter: &str, input: Value) -> Vec<Value> {
+        run(filter, &input).unwrap()
+    }
+
+    #[test]
+    fn paths_and_iteration() {
+        let input = json!({"users": [{"name": "a", "age": 30}, {"name": "b", "age": 20}]});
+        assert_eq!(query(".users[0].name", input.clone()), [json!("a")]);
+        assert_eq!(query(".users[].age", input.clone()), [json!(30), json!(20)]);
+        assert_eq!(query(".users[-1:] | length", input.clone()), [json!(1)]);
+        assert_eq!(query(".missing?.x", input), [Value::Null]);
+    }
+
+    #[test]
+    fn filters_and_builtins() {
+        let input = json!([3, 1, 2]);
+        assert_eq!(query("map(. * 2)", input.clone()), [json!([6, 2, 4])]);
+        assert_eq!(query("sort | reverse", input.clone()), [json!([3, 2, 1])]);
+        assert_eq!(
+            query(".[] | select(. > 1)", input.clone()),
+            [json!(3), json!(2)]
+        );
+        assert_eq!(query("add / length", input.clone()), [json!(2)]);
+        assert_eq!(
+            query("if length > 2 then \"long\" else \"short\" end", input),
+            [json!("long")]
+        );
+        assert_eq!(query(".a // \"default\"", json!({})), [json!("default")]);
+        assert_eq!(
+            query(
+                "{name: .n, tags: [.t[] | ascii_upcase]}",
+                json!({"n": 1, "t": ["x"]})
+            ),
+            [json!({"name": 1, "tags": ["X"]})]
+        );
+    }
+
+    #[test]
+    fn large_integers_stay_exact() {
+        let input = json!([{"id": 9007199254740993u64}, {"id": 9007199254740992u64}]);
+        assert_eq!(
+            query(".[] | select(.id == 9007199254740993) | .id", input.clone()),
+            [json!(9007199254740993u64)]
+        );
+        assert_eq!(
+            query("sort_by(.id) | map(.id)", input.clone()),
+            [json!([9007199254740992u64, 9007199254740993u64])]
+        );
+        assert_eq!(
+            query(".[0].id + 1, -.[0].id", input.clone()),
+            [json!(9007199254740994u64), json!(-9007199254740993i64)]
+        );
+        assert_eq!(
+            query("-.[0].id | length", input.clone()),
+            [json!(9007199254740993u64)]
+        );
+        assert_eq!(
+            query("1.5 + 1, -1.5 | length", Value::Null),
+            [json!(2.5), json!(1.5)]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(run(".[", &Value::Null).is_err());
+        assert!(run("1 / 0", &Value::Null).is_err());
+        assert!(run("{} - 1", &Value::Null).is_err());
+        assert!(run("nosuchfunction", &Value::Null).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use super::binary::{encode_bytes, read_raw};
+use super::{_base_64_decode_bytes, _text_input, charset};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+
+pub fn quoted_printable_encode(a: &Args, t: &Term) {
+    let msg = "Enter a string to quoted-printable encode:";
+    let (data, file, charset) = match &a.tool {
+        Some(Tools::QuotedPrintableEncode {
+            data,
+            file,
+            charset,
+        }) => (data.clone(), file.clone(), charset.clone()),
+        _ => (None, None, "utf-8".to_string()),
+    };
+    // Files are encoded byte for byte, only typed text is converted to the charset
+    let bytes = match file {
+        Some(_) => read_raw(t, msg, None, file),
+        None => charset::encode(&_text_input(t, msg, data), &charset).map(|(bytes, _)| bytes),
+    };
+    match bytes {
+        Ok(bytes) => success(t, encode_quoted_printable(&bytes).as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn quoted_printable_decode(a: &Args, t: &Term) {
+    let msg =
+        "Enter some quoted-printable text to decode (type 'END' on a new line to stop input):";
+    let (data, charset) = match &a.tool {
+        Some(Tools::QuotedPrintableDecode { data, charset }) => (data.clone(), charset.clone()),
+        _ => (None, "utf-8".to_string()),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    match charset::decode(&decode_quoted_printable(&input), &charset) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn encoded_word_encode(a: &Args, t: &Term) {
+    let msg = "Enter a header value to encode:";
+    let (data, charset, encoding) = match &a.tool {
+        Some(Tools::EncodedWordEncode {
+            data,
+            charset,
+            encoding,
+        }) => (data.clone(), charset.clone(), encoding.clone()),
+        _ => (None, "utf-8".to_string(), "b".to_string()),
+    };
+    let input = _text_input(t, msg, data);
+    match _encoded_word_encode(&input, &charset, &encoding) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn encoded_word_decode(a: &Args, t: &Term) {
+    let msg = "Enter a header containing encoded words like =?UTF-8?B?...?= to decode:";
+    let data = match &a.tool {
+        Some(Tools::EncodedWordDecode { data }) => data.clone(),
+        _ => None,
+    };
+    let input = _text_input(t, msg, data);
+    match _encoded_word_decode(&input) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn is_literal(byte: u8) -> bool {
+    (33..=126).contains(&byte) && byte != b'='
+}
+
+// RFC 2045: lines are kept under 76 characters with soft breaks, and spaces or tabs at the end of
+// a line are encoded so they survive transport
+fn encode_quoted_printable(bytes: &[u8]) -> String {
+    let mut lines = vec![];
+    for line in bytes.split(|b| *b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut encoded = String::new();
+        let mut length = 0;
+        for (i, byte) in line.iter().enumerate() {
+            let last = i == line.len() - 1;
+            let token = if is_literal(*byte) || (matches!(byte, b' ' | b'\t') && !last) {
+                (*byte as char).to_string()
+            } else {
+                format!("={byte:02X}")
+            };
+            if length + token.len() > 75 {
+                encoded.push_str("=\n");
+                length = 0;
+            }
+            length += token.len();
+            encoded.push_str(&token);
+        }
+        lines.push(encoded);
+    }
+    lines.join("\n")
+}
+
+// Malformed escapes are kept as they are instead of failing, the way mail clients do
+fn decode_quoted_printable(input: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    let lines: Vec<&str> = input.split('\n').collect();
+    for (i, line) in lines.iter().enumerate() {
+        let line = line
+            .strip_suffix('\r')
+            .unwrap_or(line)
+            .trim_end_matches([' ', '\t']);
+        let (line, soft) = match line.strip_suffix('=') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        bytes.extend(unescape(line.as_bytes(), false));
+        if !soft && i < lines.len() - 1 {
+            bytes.push(b'\n');
+        }
+    }
+    bytes
+}
+
+// Shared by quoted-printable and the Q encoding, where '_' also stands for a space
+fn unescape(input: &[u8], underscores: bool) -> Vec<u8> {
+    let mut bytes = vec![];
+    let mut i = 0;
+    while i < input.len() {
+        let hex = input
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (input[i], hex) {
+            (b'=', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'_', _) if underscores => bytes.push(b' '),
+            (byte, _) => bytes.push(byte),
+        }
+        i += 1;
+    }
+    bytes
+}
+
+// RFC 2047: =?charset?encoding?text?= with B (Base64) or Q (quoted-printable like) encoding.
+// The whitespace between two encoded words is dropped
+fn _encoded_word_decode(input: &str) -> Result<String, String> {
+    let mut output = String::new();
+    let mut rest = input;
+    let mut pending_space = String::new();
+    let mut after_word = false;
+    while !rest.is_empty() {
+        let Some((start, end)) = next_word(rest) else {
+            output.push_str(&pending_space);
+            output.push_str(rest);
+            break;
+        };
+
+        let before = &rest[..start];
+        if !(after_word && before.trim().is_empty()) {
+            output.push_str(&pending_space);
+            output.push_str(before);
+        }
+        pending_space.clear();
+
+        let word = &rest[start..end];
+        let mut parts = word[2..word.len() - 2].splitn(3, '?');
+        let (charset, encoding, text) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(charset), Some(encoding), Some(text)) => (charset, encoding, text),
+            _ => return Err(format!("Invalid encoded word '{word}'")),
+        };
+        // RFC 2231 allows a language after the charset, e.g. UTF-8*en
+        let charset = charset.split('*').next().unwrap_or(charset);
+        let bytes = match encoding {
+            "B" | "b" => _base_64_decode_bytes(text)
+                .map_err(|err| format!("Invalid encoded word '{word}': {err}"))?,
+            "Q" | "q" => unescape(text.as_bytes(), true),
+            other => return Err(format!("Unknown encoded word encoding '{other}'")),
+        };
+        output.push_str(&charset::decode(&bytes, charset)?);
+
+        rest = &rest[end..];
+        let spaces = rest.len() - rest.trim_start().len();
+        pending_space = rest[..spaces].to_string();
+        rest = &rest[spaces..];
+        after_word = true;
+    }
+    Ok(output)
+}
+
+// Finds the next =?charset?encoding?text?= and returns where it starts and ends
+fn next_word(input: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    while let Some(offset) = input[from..].find("=?") {
+        let start = from + offset;
+        let inner = &input[start + 2..];
+        let question_marks: Vec<usize> = inner.match_indices('?').map(|(i, _)| i).collect();
+        // The charset and encoding end at the first two '?', the text at the next "?="
+        if let [_, second, ..] = question_marks[..]
+            && let Some(end) = inner[second + 1..].find("?=")
+        {
+            return Some((start, start + 2 + second + 1 + end + 2));
+        }
+        from = start + 2;
+    }
+    None
+}
+
+// Each encoded word may be at most 75 characters long, so longer text is split into several
+// words without breaking up a character
+fn _encoded_word_encode(input: &str, charset: &str, encoding: &str) -> Result<String, String> {
+    let name = charset::encode("", charset)?.1;
+    let overhead = format!("=?{name}?{}??=", encoding.to_uppercase()).len();
+    let mut words = vec![];
+    let mut current = String::new();
+    for c in input.chars() {
+        let mut candidate = current.clone();
+        candidate.push(c);
+        let encoded = encode_word_text(&candidate, charset, encoding)?;
+        if overhead + encoded.len() > 75 && !current.is_empty() {
+            words.push(current);
+            current = c.to_string();
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() || words.is_empty() {
+        words.push(current);
+    }
+    words
+        .iter()
+        .map(|word| {
+            let text = encode_word_text(word, charset, encoding)?;
+            Ok(format!("=?{name}?{}?{text}?=", encoding.to_uppercase()))
+        })
+        .collect::<Result<Vec<String>, String>>()
+        .map(|words| words.join(" "))
+}
+
+fn encode_word_text(text: &str, charset: &str, encoding: &str) -> Result<String, String> {
+    let (bytes, _) = charset::encode(text, charset)?;
+    if encoding == "b" {
+        return Ok(encode_bytes(&bytes, "base64"));
+    }
+    Ok(bytes
+        .iter()
+        .map(|byte| match byte {
+            b' ' => "_".to_string(),
+            b if b.is_ascii_alphanumeric() || b"!*+-/".contains(b) => (*b as char).to_string(),
+            b => format!("={b:02X}"),
+        })
+        .collect())
+}
@@ -0,0 +1,186 @@
+use super::_base_64_decode_bytes;
+use super::binary::{as_binary, as_float, binary, float, key_string, read_bytes, write_bytes};
+use super::formats::parse_any;
+use crate::{Args, Tools, error, print, read_input, success};
+use base64::{Engine as _, engine::general_purpose};
+use console::Term;
+use rmpv::Value as MsgPack;
+use serde_json::{Map, Value, json};
+
+pub fn msgpack_to_json(a: &Args, t: &Term) {
+    let msg = "Enter MessagePack data as hex or Base64 to convert to JSON:";
+    let (data, file, encoding) = match &a.tool {
+        Some(Tools::MsgpackToJSON {
+            data,
+            file,
+            encoding,
+        }) => (data.clone(), file.clone(), encoding.clone()),
+        _ => (None, None, "auto".to_string()),
+    };
+    let result = read_bytes(t, msg, data, file, &encoding).and_then(|bytes| {
+        serde_json::to_string_pretty(&decode(&bytes)?).map_err(|err| err.to_string())
+    });
+    match result {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_msgpack(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to MessagePack (type 'END' on a new line to stop input):";
+    let (data, encoding, out) = match &a.tool {
+        Some(Tools::JSONToMsgpack {
+            data,
+            encoding,
+            out,
+        }) => (data.clone(), encoding.clone(), out.clone()),
+        _ => (None, "base64".to_string(), None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    let result = parse_any(&input).and_then(|(value, _)| {
+        let mut bytes = vec![];
+        rmpv::encode::write_value(&mut bytes, &from_json(&value)?)
+            .map_err(|err| format!("Error encoding MessagePack: {err}"))?;
+        write_bytes(t, &bytes, &encoding, out)
+    });
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = bytes;
+    let value = rmpv::decode::read_value(&mut reader)
+        .map_err(|err| format!("Error decoding MessagePack: {err}"))?;
+    if !reader.is_empty() {
+        return Err(format!(
+            "{} unexpected bytes after the MessagePack value",
+            reader.len()
+        ));
+    }
+    Ok(to_json(value))
+}
+
+fn to_json(value: MsgPack) -> Value {
+    match value {
+        MsgPack::Nil => Value::Null,
+        MsgPack::Boolean(b) => Value::Bool(b),
+        MsgPack::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => json!(i),
+            (_, Some(u)) => json!(u),
+            _ => Value::Null,
+        },
+        MsgPack::F32(f) => float(f as f64),
+        MsgPack::F64(f) => float(f),
+        // Strings that aren't valid UTF-8 are kept as their bytes
+        MsgPack::String(s) => match s.as_str() {
+            Some(text) => Value::String(text.to_string()),
+            None => binary(s.as_bytes()),
+        },
+        MsgPack::Binary(bytes) => binary(&bytes),
+        MsgPack::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        MsgPack::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key_string(to_json(key)), to_json(value)))
+                .collect(),
+        ),
+        MsgPack::Ext(kind, data) => json!({
+            "$ext": {
+                "type": kind,
+                "data": general_purpose::STANDARD.encode(data),
+            }
+        }),
+    }
+}
+
+fn from_json(value: &Value) -> Result<MsgPack, String> {
+    Ok(match value {
+        Value::Null => MsgPack::Nil,
+        Value::Bool(b) => MsgPack::Boolean(*b),
+        Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+            (Some(u), _, _) => MsgPack::from(u),
+            (_, Some(i), _) => MsgPack::from(i),
+            (_, _, Some(f)) => MsgPack::F64(f),
+            _ => return Err(format!("Unsupported number {n}")),
+        },
+        Value::String(s) => MsgPack::from(s.as_str()),
+        Value::Array(items) => {
+            MsgPack::Array(items.iter().map(from_json).collect::<Result<_, _>>()?)
+        }
+        Value::Object(object) => {
+            if let Some(bytes) = as_binary(object) {
+                return Ok(MsgPack::Binary(bytes?));
+            }
+            if let Some(f) = as_float(object) {
+                return Ok(MsgPack::F64(f?));
+            }
+            if let Some(ext) = as_ext(object) {
+                let (kind, data) = ext?;
+                return Ok(MsgPack::Ext(kind, data));
+            }
+            MsgPack::Map(
+                object
+                    .iter()
+                    .map(|(key, value)| Ok((MsgPack::from(key.as_str()), from_json(value)?)))
+                    .collect::<Result<_, String>>()?,
+            )
+        }
+    })
+}
+
+fn as_ext(object: &Map<String, Value>) -> Option<Result<(i8, Vec<u8>), String>> {
+    let ext = match (object.len(), object.get("$ext")) {
+        (1, Some(Value::Object(ext))) => ext,
+        _ => return None,
+    };
+    let kind = ext
+        .get("type")
+        .and_then(Value::as_i64)
+        .and_then(|kind| i8::try_from(kind).ok());
+    let data = ext.get("data").and_then(Value::as_str);
+    Some(match (kind, data) {
+        (Some(kind), Some(data)) => _base_64_decode_bytes(data).map(|data| (kind, data)),
+        _ => Err("$ext needs an integer type from -128 to 127 and Base64 data".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, from_json, to_json};
+    use serde_json::json;
+
+    #[test]
+    fn round_trip() {
+        let value = json!({
+            "int": -5,
+            "big": u64::MAX,
+            "float": 1.5,
+            "text": "café",
+            "list": [null, true, {"nested": []}],
+            "bytes": {"$binary": {"base64": "AAEC", "subType": "00"}},
+            "ext": {"$ext": {"type": 1, "data": "AQ=="}},
+        });
+        let mut bytes = vec![];
+        rmpv::encode::write_value(&mut bytes, &from_json(&value).unwrap()).unwrap();
+        let decoded = rmpv::decode::read_value(&mut bytes.as_slice()).unwrap();
+        assert_eq!(to_json(decoded), value);
+    }
+
+    #[test]
+    fn truncated_and_trailing_input() {
+        assert_eq!(decode(&[0x92, 0x01, 0x02]).unwrap(), json!([1, 2]));
+        assert!(
+            decode(&[0x92, 0x01])
+                .unwrap_err()
+                .starts_with("Error decoding MessagePack")
+        );
+        assert_eq!(
+            decode(&[0x01, 0x02]).unwrap_err(),
+            "1 unexpected bytes after the MessagePack value"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{error, success};
+use console::Term;
+use serde_json::Value;
+
+// Parses every non-blank line on its own, keeping the 1-based line number for error reporting
+pub fn parse_lines(input: &str) -> Vec<(usize, Result<Value, String>)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            (
+                index + 1,
+                serde_json::from_str(line).map_err(|err| err.to_string()),
+            )
+        })
+        .collect()
+}
+
+// Bad lines are reported and skipped so one broken log line doesn't stop the rest
+pub fn process_lines<F>(t: &Term, input: &str, mut f: F)
+where
+    F: FnMut(Value) -> Result<Vec<String>, String>,
+{
+    for (line, value) in parse_lines(input) {
+        match value.and_then(&mut f) {
+            Ok(outputs) => {
+                for output in outputs {
+                    success(t, output.as_str(), None, true);
+                }
+            }
+            Err(err) => error(t, err.as_str(), Some(format!("Line {line}").as_str())),
+        }
+    }
+}
+
+pub fn collect_lines(t: &Term, input: &str) -> Value {
+    let mut values = vec![];
+    for (line, value) in parse_lines(input) {
+        match value {
+            Ok(value) => values.push(value),
+            Err(err) => error(t, err.as_str(), Some(format!("Line {line}").as_str())),
+        }
+    }
+    Value::Array(values)
+}
+
+pub fn split_lines(value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Array(items) => Ok(items.iter().map(Value::to_string).collect()),
+        _ => Err("Only a JSON array can be split into lines".to_string()),
+    }
+}
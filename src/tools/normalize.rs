@@ -0,0 +1,298 @@
+use super::_text_input;
+use crate::{Args, Tools, error, success};
+use console::Term;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+const HALF_KANA: &str = "｡｢｣､･ｦｧｨｩｪｫｬｭｮｯｰｱｲｳｴｵｶｷｸｹｺｻｼｽｾｿﾀﾁﾂﾃﾄﾅﾆﾇﾈﾉﾊﾋﾌﾍﾎﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜﾝ";
+const FULL_KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+struct Options {
+    form: String,
+    case_fold: bool,
+    width: Option<String>,
+    strip_diacritics: bool,
+}
+
+pub fn unicode_normalize(a: &Args, t: &Term) {
+    let msg = "Enter a string to normalize:";
+    let (data, options, check) = match &a.tool {
+        Some(Tools::UnicodeNormalize {
+            data,
+            form,
+            case_fold,
+            width,
+            strip_diacritics,
+            check,
+        }) => (
+            data.clone(),
+            Options {
+                form: form.clone(),
+                case_fold: *case_fold,
+                width: width.clone(),
+                strip_diacritics: *strip_diacritics,
+            },
+            *check,
+        ),
+        _ => (
+            None,
+            Options {
+                form: "nfc".to_string(),
+                case_fold: false,
+                width: None,
+                strip_diacritics: false,
+            },
+            false,
+        ),
+    };
+    let input = _text_input(t, msg, data);
+
+    if !check {
+        success(t, &_unicode_normalize(&input, &options), None, a.plain);
+        return;
+    }
+    // Normalization doesn't reach across grapheme clusters, so they can be compared one by one
+    let changed: Vec<(&str, String)> = input
+        .graphemes(true)
+        .map(|cluster| (cluster, _unicode_normalize(cluster, &options)))
+        .filter(|(cluster, normalized)| cluster != normalized)
+        .collect();
+    let applied = applied(&options);
+    if changed.is_empty() {
+        let line = format!("The text doesn't change with {applied}");
+        success(t, &line, None, a.plain);
+        return;
+    }
+    error(
+        t,
+        &format!("{} character(s) change with {applied}", changed.len()),
+        Some("Not normalized"),
+    );
+    if a.plain {
+        return;
+    }
+    for (cluster, normalized) in changed.iter().take(20) {
+        let line = format!(
+            "{} becomes {}",
+            code_points(cluster),
+            code_points(normalized)
+        );
+        error(t, &line, Some(format!("'{cluster}'").as_str()));
+    }
+    if changed.len() > 20 {
+        error(t, &format!("{} more", changed.len() - 20), Some("..."));
+    }
+}
+
+fn _unicode_normalize(input: &str, options: &Options) -> String {
+    let mut text = input.to_string();
+    if options.case_fold {
+        text = case_fold(&text);
+    }
+    if options.strip_diacritics {
+        text = strip_diacritics(&text);
+    }
+    let compose = matches!(options.form.as_str(), "nfc" | "nfkc");
+    text = match options.form.as_str() {
+        "nfd" => text.nfd().collect(),
+        "nfkc" => text.nfkc().collect(),
+        "nfkd" => text.nfkd().collect(),
+        _ => text.nfc().collect(),
+    };
+    // Last, since the compatibility forms would turn full-width characters back into ASCII
+    match options.width.as_deref() {
+        Some("half") => to_half_width(&text),
+        Some("full") => to_full_width(&text, compose),
+        _ => text,
+    }
+}
+
+// Lists what --check compares against, e.g. "NFC, case folding and half-width"
+fn applied(options: &Options) -> String {
+    let mut steps = vec![options.form.to_uppercase()];
+    if options.case_fold {
+        steps.push("case folding".to_string());
+    }
+    if options.strip_diacritics {
+        steps.push("diacritics stripped".to_string());
+    }
+    if let Some(width) = &options.width {
+        steps.push(format!("{width}-width"));
+    }
+    match steps.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {last}", rest.join(", ")),
+        _ => steps.join(""),
+    }
+}
+
+// Unicode's full case folding mostly comes down to uppercasing and then lowercasing, which also
+// turns 'ß' into "ss" and 'ς' into 'σ'. The exceptions are capital 'ẞ', which uppercasing leaves
+// alone, and Cherokee, which folds to the uppercase letters
+fn case_fold(text: &str) -> String {
+    let mut folded = String::new();
+    for c in text.chars() {
+        match c {
+            'ẞ' => folded.push_str("ss"),
+            '\u{13a0}'..='\u{13f5}' | '\u{13f8}'..='\u{13fd}' | '\u{ab70}'..='\u{abbf}' => {
+                folded.extend(c.to_uppercase())
+            }
+            _ => folded.extend(c.to_uppercase().flat_map(char::to_lowercase)),
+        }
+    }
+    folded
+}
+
+// Only marks from the combining diacritics blocks are removed, since the marks of scripts like
+// Devanagari or the Japanese voicing marks change which letter it is
+fn strip_diacritics(text: &str) -> String {
+    text.nfd()
+        .filter(|c| {
+            !matches!(c, '\u{300}'..='\u{36f}'
+                | '\u{1ab0}'..='\u{1aff}'
+                | '\u{1dc0}'..='\u{1dff}'
+                | '\u{20d0}'..='\u{20ff}'
+                | '\u{fe20}'..='\u{fe2f}')
+        })
+        .map(|c| match c {
+            'ø' => 'o',
+            'Ø' => 'O',
+            'ł' => 'l',
+            'Ł' => 'L',
+            'đ' => 'd',
+            'Đ' => 'D',
+            'ħ' => 'h',
+            'Ħ' => 'H',
+            'ı' => 'i',
+            c => c,
+        })
+        .nfc()
+        .collect()
+}
+
+// Full-width ASCII and the ideographic space become ASCII, and katakana becomes the half-width
+// forms, with the voicing marks as separate characters
+fn to_half_width(text: &str) -> String {
+    let mut half = String::new();
+    for c in text.chars() {
+        match c {
+            '\u{ff01}'..='\u{ff5e}' => half.extend(char::from_u32(c as u32 - 0xfee0)),
+            '\u{3000}' => half.push(' '),
+            _ => {
+                let decomposed: Vec<char> = c.to_string().nfd().collect();
+                let base = FULL_KANA.chars().position(|kana| kana == decomposed[0]);
+                let mark = match decomposed.get(1) {
+                    None => Some(None),
+                    Some('\u{3099}') => Some(Some('ﾞ')),
+                    Some('\u{309a}') => Some(Some('ﾟ')),
+                    Some(_) => None,
+                };
+                match (base, mark) {
+                    (Some(index), Some(mark)) if decomposed.len() <= 2 => {
+                        half.extend(HALF_KANA.chars().nth(index));
+                        half.extend(mark);
+                    }
+                    _ if matches!(c, '゛' | '\u{3099}') => half.push('ﾞ'),
+                    _ if matches!(c, '゜' | '\u{309a}') => half.push('ﾟ'),
+                    _ => half.push(c),
+                }
+            }
+        }
+    }
+    half
+}
+
+fn to_full_width(text: &str, compose: bool) -> String {
+    let mut full = String::new();
+    let mut after_kana = false;
+    for c in text.chars() {
+        let kana = HALF_KANA
+            .chars()
+            .position(|kana| kana == c)
+            .and_then(|index| FULL_KANA.chars().nth(index));
+        match c {
+            '!'..='~' => full.extend(char::from_u32(c as u32 + 0xfee0)),
+            ' ' => full.push('\u{3000}'),
+            'ﾞ' | 'ﾟ' if after_kana => {
+                let mark = if c == 'ﾞ' { '\u{3099}' } else { '\u{309a}' };
+                let base = full.pop().unwrap_or_default();
+                match compose {
+                    true => full.extend(format!("{base}{mark}").nfc()),
+                    false => full.extend([base, mark]),
+                }
+            }
+            'ﾞ' => full.push('゛'),
+            'ﾟ' => full.push('゜'),
+            _ => full.extend(kana.or(Some(c))),
+        }
+        after_kana = kana.is_some();
+    }
+    full
+}
+
+fn code_points(text: &str) -> String {
+    let points: Vec<String> = text
+        .chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect();
+    match points.is_empty() {
+        true => "nothing".to_string(),
+        false => points.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{_unicode_normalize, Options, applied, case_fold};
+
+    fn options(form: &str, case_fold: bool, width: Option<&str>) -> Options {
+        Options {
+            form: form.to_string(),
+            case_fold,
+            width: width.map(str::to_string),
+            strip_diacritics: false,
+        }
+    }
+
+    #[test]
+    fn case_folding() {
+        assert_eq!(case_fold("STRAẞE"), case_fold("Straße"));
+        assert_eq!(case_fold("Straße"), "strasse");
+        assert_eq!(case_fold("ΣΊΣΥΦΟΣ"), case_fold("σίσυφος"));
+        // Cherokee folds to the uppercase letters
+        assert_eq!(case_fold("\u{ab70}\u{13a0}"), "\u{13a0}\u{13a0}");
+    }
+
+    #[test]
+    fn forms_and_width() {
+        let nfd = "Cafe\u{301}";
+        assert_eq!(
+            _unicode_normalize(nfd, &options("nfc", false, None)),
+            "Café"
+        );
+        assert_eq!(
+            _unicode_normalize("Café", &options("nfd", false, None)),
+            nfd
+        );
+        assert_eq!(
+            _unicode_normalize("Ｓｔｒａßｅ", &options("nfkc", true, None)),
+            "strasse"
+        );
+        assert_eq!(
+            _unicode_normalize("ｶﾞｷﾞ AB", &options("nfc", false, Some("full"))),
+            "ガギ\u{3000}ＡＢ"
+        );
+        assert_eq!(
+            _unicode_normalize("ガギ\u{3000}ＡＢ", &options("nfc", false, Some("half"))),
+            "ｶﾞｷﾞ AB"
+        );
+    }
+
+    #[test]
+    fn check_describes_the_options() {
+        assert_eq!(applied(&options("nfc", false, None)), "NFC");
+        assert_eq!(
+            applied(&options("nfkc", true, Some("half"))),
+            "NFKC, case folding and half-width"
+        );
+    }
+}
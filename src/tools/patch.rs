@@ -0,0 +1,76 @@
+use super::formats::{parse_any, parse_file, render};
+use crate::{Args, Tools, error, print, read_input, success};
+use ::json_patch::{Patch, PatchOperation};
+use console::Term;
+
+pub fn json_patch(a: &Args, t: &Term) {
+    let (patch, data) = match &a.tool {
+        Some(Tools::JSONPatch { patch, data }) => (patch.clone(), data.clone()),
+        _ => (None, None),
+    };
+    let (patch, input) = read_patch_and_document(t, patch, data);
+    match _json_patch(&patch, &input) {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), Some("Error applying patch")),
+    }
+}
+
+pub fn json_merge_patch(a: &Args, t: &Term) {
+    let (patch, data) = match &a.tool {
+        Some(Tools::JSONMergePatch { patch, data }) => (patch.clone(), data.clone()),
+        _ => (None, None),
+    };
+    let (patch, input) = read_patch_and_document(t, patch, data);
+    match _json_merge_patch(&patch, &input) {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), Some("Error applying merge patch")),
+    }
+}
+
+fn read_patch_and_document(
+    t: &Term,
+    patch: Option<String>,
+    data: Option<String>,
+) -> (String, String) {
+    let patch = patch.unwrap_or_else(|| {
+        print(t, "Enter the path of the patch file:");
+        read_input(t, None, None)
+    });
+    let input = data.unwrap_or_else(|| {
+        print(
+            t,
+            "Enter the JSON or YAML document to patch (type 'END' on a new line to stop input):",
+        );
+        read_input(t, Some(true), Some("END"))
+    });
+    (patch, input)
+}
+
+fn _json_patch(patch_path: &str, input: &str) -> Result<String, String> {
+    let (patch, _) = parse_file(patch_path)?;
+    let patch: Patch =
+        serde_json::from_value(patch).map_err(|err| format!("Invalid JSON Patch: {err}"))?;
+    let (mut doc, format) = parse_any(input)?;
+    if let Err(err) = ::json_patch::patch(&mut doc, &patch) {
+        let op = match &patch.0[err.operation] {
+            PatchOperation::Add(_) => "add",
+            PatchOperation::Remove(_) => "remove",
+            PatchOperation::Replace(_) => "replace",
+            PatchOperation::Move(_) => "move",
+            PatchOperation::Copy(_) => "copy",
+            PatchOperation::Test(_) => "test",
+        };
+        return Err(format!(
+            "Operation {} ({op}) failed at path '{}': {}",
+            err.operation, err.path, err.kind
+        ));
+    }
+    render(&doc, format)
+}
+
+fn _json_merge_patch(patch_path: &str, input: &str) -> Result<String, String> {
+    let (patch, _) = parse_file(patch_path)?;
+    let (mut doc, format) = parse_any(input)?;
+    ::json_patch::merge(&mut doc, &patch);
+    render(&doc, format)
+}
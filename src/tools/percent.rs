@@ -0,0 +1,120 @@
+use super::_text_input;
+use crate::{Args, Tools, error, success};
+use console::Term;
+use percent_encoding::{AsciiSet, CONTROLS, NON_ALPHANUMERIC, utf8_percent_encode};
+use url::form_urlencoded::byte_serialize;
+use urlencoding::decode;
+
+// Everything but the RFC 3986 unreserved characters, like encodeURIComponent
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// The WHATWG path set, so '/' is kept but '?' and '#' can't end the path early
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// The WHATWG special query set, which keeps '&', '=' and '?'
+const QUERY: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'\'')
+    .add(b'<')
+    .add(b'>');
+
+// Like encodeURI, except '[' and ']' are kept for IPv6 hosts
+const FULL: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+pub fn url_decode(a: &Args, t: &Term) {
+    let msg = "Enter a URL encoded string to decode:";
+    let (data, plus) = match &a.tool {
+        Some(Tools::URLDecode { data, plus }) => (data.clone(), *plus),
+        _ => (None, false),
+    };
+    let input = _text_input(t, msg, data);
+
+    match _url_decode(&input, plus) {
+        Ok(result_str) => success(t, result_str.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), Some("Error decoding URL encoded string")),
+    }
+}
+
+pub fn url_encode(a: &Args, t: &Term) {
+    let msg = "Enter a string to URL encode:";
+    let (data, mode) = match &a.tool {
+        Some(Tools::URLEncode { data, mode }) => (data.clone(), mode.clone()),
+        _ => (None, "component".to_string()),
+    };
+    let input = _text_input(t, msg, data);
+
+    let result = _url_encode(&input, &mode);
+    success(t, result.as_str(), None, a.plain);
+}
+
+pub fn _url_decode(input: &str, plus: bool) -> Result<String, String> {
+    let input = match plus {
+        true => input.replace('+', " "),
+        false => input.to_string(),
+    };
+    decode(&input)
+        .map(|result| result.into_owned())
+        .map_err(|err| err.to_string())
+}
+
+pub fn _url_encode(input: &str, mode: &str) -> String {
+    match mode {
+        "form" => byte_serialize(input.as_bytes()).collect(),
+        "path" => encode_keeping_escapes(input, PATH),
+        "query" => encode_keeping_escapes(input, QUERY),
+        "full" => encode_keeping_escapes(input, FULL),
+        _ => utf8_percent_encode(input, COMPONENT).to_string(),
+    }
+}
+
+// Escapes that are already there are left alone so a partly encoded URL isn't encoded twice, but
+// a '%' that doesn't start an escape is still encoded
+fn encode_keeping_escapes(input: &str, set: &'static AsciiSet) -> String {
+    let bytes = input.as_bytes();
+    let mut encoded = String::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes[i] == b'%'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+            && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+        if escape {
+            encoded.extend(utf8_percent_encode(&input[start..i], set));
+            encoded.push_str(&input[i..i + 3]);
+            i += 3;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    encoded.extend(utf8_percent_encode(&input[start..], set));
+    encoded
+}
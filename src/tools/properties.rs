@@ -0,0 +1,171 @@
+use super::flatten::flatten;
+use serde_json::{Map, Value};
+
+// Follows java.util.Properties: `#` and `!` comments, `=`, `:` or whitespace separators,
+// backslash line continuations and `\uXXXX` escapes
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut properties = Map::new();
+    let mut lines = input.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let mut logical = line.trim_start().to_string();
+        if logical.is_empty() || logical.starts_with('#') || logical.starts_with('!') {
+            continue;
+        }
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let chars: Vec<char> = logical.chars().collect();
+        let mut key_end = 0;
+        while key_end < chars.len() {
+            match chars[key_end] {
+                '\\' => key_end += 2,
+                '=' | ':' => break,
+                c if c.is_whitespace() => break,
+                _ => key_end += 1,
+            }
+        }
+        let key_end = key_end.min(chars.len());
+        let mut value_start = key_end;
+        while value_start < chars.len() && chars[value_start].is_whitespace() {
+            value_start += 1;
+        }
+        if value_start < chars.len() && matches!(chars[value_start], '=' | ':') {
+            value_start += 1;
+            while value_start < chars.len() && chars[value_start].is_whitespace() {
+                value_start += 1;
+            }
+        }
+        let key: String = chars[..key_end].iter().collect();
+        let value: String = chars[value_start..].iter().collect();
+        properties.insert(
+            unescape(&key).map_err(|err| format!("Line {line_number}: {err}"))?,
+            Value::String(unescape(&value).map_err(|err| format!("Line {line_number}: {err}"))?),
+        );
+    }
+    Ok(Value::Object(properties))
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+fn unescape(raw: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("invalid unicode escape \\u{hex}"))?;
+                // Characters outside the BMP are written as UTF-16 surrogate pairs
+                let code = if (0xD800..0xDC00).contains(&code) {
+                    let low = chars
+                        .as_str()
+                        .strip_prefix("\\u")
+                        .and_then(|rest| rest.get(..4))
+                        .and_then(|low| u32::from_str_radix(low, 16).ok())
+                        .filter(|low| (0xDC00..0xE000).contains(low))
+                        .ok_or(format!("\\u{hex} isn't followed by a low surrogate"))?;
+                    chars.by_ref().nth(5);
+                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    code
+                };
+                out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    Ok(out)
+}
+
+pub fn emit(value: &Value) -> Result<String, String> {
+    let flat = match flatten(value, ".", false) {
+        Value::Object(flat) => flat,
+        _ => return Err("Only an object can be converted to properties".to_string()),
+    };
+    Ok(flat
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            format!("{}={}", escape(key, true), escape(&value, false))
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+fn escape(text: &str, key: bool) -> String {
+    let mut out = String::new();
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{c}' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' if key || i == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if key || i == 0 => out.push_str("\\ "),
+            // Properties.load reads ISO-8859-1, so everything else is escaped as UTF-16
+            c if c.is_control() || !c.is_ascii() => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    out.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{emit, parse};
+    use serde_json::json;
+
+    #[test]
+    fn unicode_escapes() {
+        assert_eq!(
+            parse("a=caf\\u00e9 \\ud83d\\ude00").unwrap(),
+            json!({"a": "café 😀"})
+        );
+        assert!(parse("a=\\ud83dxyzabc").is_err());
+        assert!(parse("a=\\ud83d\\u0041").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = json!({"a.b": "x = y", "a.c": "tab\there", "key with space": "😀"});
+        assert_eq!(parse(&emit(&value).unwrap()).unwrap(), value);
+    }
+
+    #[test]
+    fn non_ascii_is_escaped() {
+        assert_eq!(
+            emit(&json!({"clé": "café 😀"})).unwrap(),
+            "cl\\u00E9=caf\\u00E9 \\uD83D\\uDE00"
+        );
+    }
+}
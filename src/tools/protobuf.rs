@@ -0,0 +1,859 @@
+use super::binary::read_bytes;
+use crate::{Args, Tools, error, success};
+use console::Term;
+use std::collections::HashMap;
+use std::fs;
+
+pub fn protobuf_decode(a: &Args, t: &Term) {
+    let msg = "Enter a protobuf message as hex or Base64 to decode:";
+    let (data, file, encoding, proto, message) = match &a.tool {
+        Some(Tools::ProtobufDecode {
+            data,
+            file,
+            encoding,
+            proto,
+            message,
+        }) => (
+            data.clone(),
+            file.clone(),
+            encoding.clone(),
+            proto.clone(),
+            message.clone(),
+        ),
+        _ => (None, None, "auto".to_string(), None, None),
+    };
+    let result = _protobuf_decode(t, msg, data, file, &encoding, proto, message);
+    match result {
+        Ok(tree) => success(t, tree.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn _protobuf_decode(
+    t: &Term,
+    msg: &str,
+    data: Option<String>,
+    file: Option<String>,
+    encoding: &str,
+    proto: Option<String>,
+    message: Option<String>,
+) -> Result<String, String> {
+    let schema = match &proto {
+        Some(path) => Some(Schema::load(path)?),
+        None => None,
+    };
+    let root = match &schema {
+        Some(schema) => Some(schema.root(message.as_deref())?),
+        None => None,
+    };
+    let bytes = read_bytes(t, msg, data, file, encoding)?;
+    let fields = parse_message(&bytes)?;
+    let mut lines = vec![];
+    render(&fields, schema.as_ref(), root.as_deref(), 0, &mut lines);
+    Ok(lines.join("\n"))
+}
+
+enum Wire {
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32(u32),
+    Len(Vec<u8>),
+    Group(Vec<Field>),
+}
+
+struct Field {
+    number: u64,
+    wire: Wire,
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let start = *pos;
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or(format!("Truncated varint at offset {start}"))?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(format!("Varint at offset {start} is longer than 10 bytes"))
+}
+
+fn read_fixed<const N: usize>(bytes: &[u8], pos: &mut usize) -> Result<[u8; N], String> {
+    let chunk = bytes
+        .get(*pos..*pos + N)
+        .ok_or(format!("Truncated {}-bit value at offset {pos}", N * 8))?;
+    *pos += N;
+    Ok(chunk.try_into().unwrap_or([0; N]))
+}
+
+// The same limit protoc uses, which also keeps hostile input from overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+fn parse_message(bytes: &[u8]) -> Result<Vec<Field>, String> {
+    parse_fields(bytes, &mut 0, None, 0)
+}
+
+// Groups are the deprecated proto2 encoding of nested messages, closed by an end group tag
+fn parse_fields(
+    bytes: &[u8],
+    pos: &mut usize,
+    group: Option<u64>,
+    depth: usize,
+) -> Result<Vec<Field>, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!(
+            "Groups are nested more than {MAX_DEPTH} levels deep at offset {pos}"
+        ));
+    }
+    let mut fields = vec![];
+    while *pos < bytes.len() {
+        let offset = *pos;
+        let tag = read_varint(bytes, pos)?;
+        let number = tag >> 3;
+        if number == 0 {
+            return Err(format!("Invalid field number 0 at offset {offset}"));
+        }
+        let wire = match tag & 7 {
+            0 => Wire::Varint(read_varint(bytes, pos)?),
+            1 => Wire::Fixed64(u64::from_le_bytes(read_fixed(bytes, pos)?)),
+            2 => {
+                let length = read_varint(bytes, pos)? as usize;
+                let end = pos.checked_add(length).filter(|end| *end <= bytes.len());
+                let end = end.ok_or(format!(
+                    "Length {length} at offset {offset} runs past the end of the data"
+                ))?;
+                let value = bytes[*pos..end].to_vec();
+                *pos = end;
+                Wire::Len(value)
+            }
+            3 => Wire::Group(parse_fields(bytes, pos, Some(number), depth + 1)?),
+            4 if group == Some(number) => return Ok(fields),
+            4 => return Err(format!("Unexpected end group tag at offset {offset}")),
+            5 => Wire::Fixed32(u32::from_le_bytes(read_fixed(bytes, pos)?)),
+            other => return Err(format!("Invalid wire type {other} at offset {offset}")),
+        };
+        fields.push(Field { number, wire });
+    }
+    match group {
+        Some(number) => Err(format!("Group {number} is missing its end tag")),
+        None => Ok(fields),
+    }
+}
+
+fn render(
+    fields: &[Field],
+    schema: Option<&Schema>,
+    message: Option<&str>,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    let definition = schema
+        .zip(message)
+        .and_then(|(schema, message)| schema.messages.get(message));
+    for field in fields {
+        let def = definition.and_then(|definition| definition.fields.get(&field.number));
+        let label = match def {
+            Some(def) => format!("{} ({})", def.name, field.number),
+            None => field.number.to_string(),
+        };
+        let nested = schema.zip(def).and_then(|(schema, def)| {
+            let name = def.kind.strip_prefix('.')?;
+            schema.messages.contains_key(name).then_some(name)
+        });
+
+        // Fields the schema declares as scalars or enums are never guessed to be messages
+        let scalar = def.is_some_and(|def| {
+            SCALARS.contains(&def.kind.as_str())
+                || schema.is_some_and(|schema| {
+                    def.kind
+                        .strip_prefix('.')
+                        .is_some_and(|name| schema.enums.contains_key(name))
+                })
+        });
+        // Past the depth limit nested messages are shown as bytes
+        let parsed = match &field.wire {
+            _ if depth >= MAX_DEPTH => None,
+            Wire::Len(bytes) if nested.is_some() => parse_message(bytes).ok(),
+            Wire::Len(bytes) if !scalar && !is_text(bytes) => parse_message(bytes)
+                .ok()
+                .filter(|fields| !fields.is_empty()),
+            _ => None,
+        };
+        let children = match &field.wire {
+            Wire::Group(children) => Some(children),
+            _ => parsed.as_ref(),
+        };
+        if let Some(children) = children {
+            lines.push(format!("{indent}{label} {{"));
+            render(children, schema, nested, depth + 1, lines);
+            lines.push(format!("{indent}}}"));
+            continue;
+        }
+
+        let typed = schema
+            .zip(def)
+            .and_then(|(schema, def)| typed_value(&field.wire, &def.kind, schema));
+        let value = typed.unwrap_or_else(|| schemaless_value(&field.wire));
+        lines.push(format!("{indent}{label}: {value}"));
+    }
+}
+
+// Printable UTF-8 is treated as a string, since real nested messages almost always contain
+// length bytes below 0x20
+fn is_text(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t')),
+        Err(_) => false,
+    }
+}
+
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("[{}]", hex.join(" "))
+}
+
+fn zigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+// Small integers read as floats come out as tiny denormals, which only add noise
+fn plausible(f: f64) -> bool {
+    f == 0.0 || (f.is_finite() && (1e-9..1e15).contains(&f.abs()))
+}
+
+fn schemaless_value(wire: &Wire) -> String {
+    match wire {
+        Wire::Varint(value) => {
+            let mut hints = vec![];
+            if *value > i64::MAX as u64 {
+                hints.push(format!("int64: {}", *value as i64));
+            }
+            if *value != 0 {
+                hints.push(format!("zigzag: {}", zigzag(*value)));
+            }
+            match hints.is_empty() {
+                true => value.to_string(),
+                false => format!("{value} ({})", hints.join(", ")),
+            }
+        }
+        Wire::Fixed32(value) => {
+            let mut hints = vec![format!("uint32: {value}")];
+            if (*value as i32) < 0 {
+                hints.push(format!("int32: {}", *value as i32));
+            }
+            let float = f32::from_bits(*value);
+            if plausible(float as f64) {
+                hints.push(format!("float: {float}"));
+            }
+            format!("0x{value:08x} ({})", hints.join(", "))
+        }
+        Wire::Fixed64(value) => {
+            let mut hints = vec![format!("uint64: {value}")];
+            if (*value as i64) < 0 {
+                hints.push(format!("int64: {}", *value as i64));
+            }
+            let double = f64::from_bits(*value);
+            if plausible(double) {
+                hints.push(format!("double: {double}"));
+            }
+            format!("0x{value:016x} ({})", hints.join(", "))
+        }
+        Wire::Len(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) if is_text(bytes) => quote(text),
+            _ => hex(bytes),
+        },
+        Wire::Group(_) => String::new(),
+    }
+}
+
+fn typed_value(wire: &Wire, kind: &str, schema: &Schema) -> Option<String> {
+    let enum_name = |value: u64| {
+        let values = schema.enums.get(kind.strip_prefix('.')?)?;
+        Some(match values.get(&(value as i32 as i64)) {
+            Some(name) => name.clone(),
+            None => (value as i32).to_string(),
+        })
+    };
+    match wire {
+        Wire::Varint(value) => match kind {
+            "int32" => Some((*value as i32).to_string()),
+            "int64" => Some((*value as i64).to_string()),
+            "uint32" | "uint64" => Some(value.to_string()),
+            "sint32" | "sint64" => Some(zigzag(*value).to_string()),
+            "bool" => Some((*value != 0).to_string()),
+            _ => enum_name(*value),
+        },
+        Wire::Fixed32(value) => match kind {
+            "fixed32" => Some(value.to_string()),
+            "sfixed32" => Some((*value as i32).to_string()),
+            "float" => Some(f32::from_bits(*value).to_string()),
+            _ => None,
+        },
+        Wire::Fixed64(value) => match kind {
+            "fixed64" => Some(value.to_string()),
+            "sfixed64" => Some((*value as i64).to_string()),
+            "double" => Some(f64::from_bits(*value).to_string()),
+            _ => None,
+        },
+        Wire::Len(bytes) => match kind {
+            "string" => Some(quote(&String::from_utf8_lossy(bytes))),
+            "bytes" => Some(hex(bytes)),
+            // Repeated scalars are packed into a single length delimited field
+            _ => packed(bytes, kind, schema).map(|values| format!("[{}]", values.join(", "))),
+        },
+        Wire::Group(_) => None,
+    }
+}
+
+fn packed(bytes: &[u8], kind: &str, schema: &Schema) -> Option<Vec<String>> {
+    let mut pos = 0;
+    let mut values = vec![];
+    while pos < bytes.len() {
+        let wire = match kind {
+            "fixed32" | "sfixed32" | "float" => {
+                Wire::Fixed32(u32::from_le_bytes(read_fixed(bytes, &mut pos).ok()?))
+            }
+            "fixed64" | "sfixed64" | "double" => {
+                Wire::Fixed64(u64::from_le_bytes(read_fixed(bytes, &mut pos).ok()?))
+            }
+            _ => Wire::Varint(read_varint(bytes, &mut pos).ok()?),
+        };
+        values.push(typed_value(&wire, kind, schema)?);
+    }
+    Some(values)
+}
+
+struct FieldDef {
+    name: String,
+    // A scalar type name, or a fully qualified message or enum name with a leading dot
+    kind: String,
+}
+
+#[derive(Default)]
+struct MessageDef {
+    fields: HashMap<u64, FieldDef>,
+}
+
+#[derive(Default)]
+struct Schema {
+    messages: HashMap<String, MessageDef>,
+    enums: HashMap<String, HashMap<i64, String>>,
+    order: Vec<String>,
+}
+
+const SCALARS: [&str; 15] = [
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+fn join(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+impl Schema {
+    // `.proto` files are parsed directly, anything else is read as a descriptor set from
+    // `protoc --descriptor_set_out`
+    fn load(path: &str) -> Result<Schema, String> {
+        let mut schema = if path.ends_with(".proto") {
+            let source =
+                fs::read_to_string(path).map_err(|err| format!("Error reading {path}: {err}"))?;
+            Schema::from_proto(&source).map_err(|err| format!("{path}: {err}"))?
+        } else {
+            let bytes = fs::read(path).map_err(|err| format!("Error reading {path}: {err}"))?;
+            Schema::from_descriptor_set(&bytes)
+                .map_err(|err| format!("{path} is not a valid descriptor set: {err}"))?
+        };
+        schema.resolve_types();
+        Ok(schema)
+    }
+
+    fn root(&self, message: Option<&str>) -> Result<String, String> {
+        let name = match message {
+            Some(name) => name.trim_start_matches('.'),
+            None => {
+                return self
+                    .order
+                    .first()
+                    .cloned()
+                    .ok_or("The schema doesn't define any messages".to_string());
+            }
+        };
+        if self.messages.contains_key(name) {
+            return Ok(name.to_string());
+        }
+        let suffix = format!(".{name}");
+        let matches: Vec<&String> = self
+            .order
+            .iter()
+            .filter(|full| full.ends_with(&suffix))
+            .collect();
+        match matches.as_slice() {
+            [full] => Ok(full.to_string()),
+            [] => Err(format!("Message '{name}' not found in the schema")),
+            _ => Err(format!(
+                "Message '{name}' is ambiguous, use one of: {}",
+                matches
+                    .iter()
+                    .map(|full| full.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    // Type names are looked up from the innermost scope outwards, like protoc does
+    fn resolve_types(&mut self) {
+        let mut resolved = vec![];
+        for (message, definition) in &self.messages {
+            for (number, field) in &definition.fields {
+                if SCALARS.contains(&field.kind.as_str()) || field.kind.starts_with('.') {
+                    continue;
+                }
+                let mut scope = message.as_str();
+                loop {
+                    let candidate = join(scope, &field.kind);
+                    if self.messages.contains_key(&candidate) || self.enums.contains_key(&candidate)
+                    {
+                        resolved.push((message.clone(), *number, format!(".{candidate}")));
+                        break;
+                    }
+                    if scope.is_empty() {
+                        break;
+                    }
+                    scope = scope.rsplit_once('.').map_or("", |(parent, _)| parent);
+                }
+            }
+        }
+        for (message, number, kind) in resolved {
+            if let Some(field) = self
+                .messages
+                .get_mut(&message)
+                .and_then(|definition| definition.fields.get_mut(&number))
+            {
+                field.kind = kind;
+            }
+        }
+    }
+
+    fn from_descriptor_set(bytes: &[u8]) -> Result<Schema, String> {
+        let mut schema = Schema::default();
+        for file in parse_message(bytes)? {
+            let Wire::Len(file) = file.wire else { continue };
+            let fields = parse_message(&file)?;
+            let package = string_field(&fields, 2).unwrap_or_default();
+            for field in &fields {
+                match (field.number, &field.wire) {
+                    (4, Wire::Len(message)) => schema.add_message_descriptor(&package, message)?,
+                    (5, Wire::Len(values)) => schema.add_enum_descriptor(&package, values)?,
+                    _ => {}
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    fn add_message_descriptor(&mut self, scope: &str, bytes: &[u8]) -> Result<(), String> {
+        let fields = parse_message(bytes)?;
+        let name = join(scope, &string_field(&fields, 1).unwrap_or_default());
+        self.order.push(name.clone());
+        let mut definition = MessageDef::default();
+        for field in &fields {
+            match (field.number, &field.wire) {
+                (2, Wire::Len(field)) => {
+                    let field = parse_message(field)?;
+                    let number = varint_field(&field, 3).unwrap_or_default();
+                    let kind = match varint_field(&field, 5) {
+                        Some(10 | 11 | 14) => string_field(&field, 6).unwrap_or_default(),
+                        Some(kind) => descriptor_scalar(kind).to_string(),
+                        None => string_field(&field, 6).unwrap_or_default(),
+                    };
+                    let name = string_field(&field, 1).unwrap_or_default();
+                    definition.fields.insert(number, FieldDef { name, kind });
+                }
+                (3, Wire::Len(nested)) => self.add_message_descriptor(&name, nested)?,
+                (4, Wire::Len(values)) => self.add_enum_descriptor(&name, values)?,
+                _ => {}
+            }
+        }
+        self.messages.insert(name, definition);
+        Ok(())
+    }
+
+    fn add_enum_descriptor(&mut self, scope: &str, bytes: &[u8]) -> Result<(), String> {
+        let fields = parse_message(bytes)?;
+        let name = join(scope, &string_field(&fields, 1).unwrap_or_default());
+        let mut values = HashMap::new();
+        for field in &fields {
+            if let (2, Wire::Len(value)) = (field.number, &field.wire) {
+                let value = parse_message(value)?;
+                let number = varint_field(&value, 2).unwrap_or_default() as i32 as i64;
+                values.insert(number, string_field(&value, 1).unwrap_or_default());
+            }
+        }
+        self.enums.insert(name, values);
+        Ok(())
+    }
+
+    fn from_proto(source: &str) -> Result<Schema, String> {
+        let mut parser = ProtoParser {
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+        let mut schema = Schema::default();
+        let mut package = String::new();
+        while let Some(token) = parser.next() {
+            match token.as_str() {
+                "package" => {
+                    package = parser.next().ok_or("Expected a package name")?;
+                    parser.skip_statement();
+                }
+                "syntax" | "edition" | "import" | "option" => parser.skip_statement(),
+                "message" => parser.message(&package, &mut schema)?,
+                "enum" => parser.enumeration(&package, &mut schema)?,
+                "service" | "extend" => parser.skip_statement(),
+                ";" => {}
+                other => return Err(format!("Unexpected '{other}'")),
+            }
+        }
+        Ok(schema)
+    }
+}
+
+fn string_field(fields: &[Field], number: u64) -> Option<String> {
+    fields.iter().find_map(|field| match &field.wire {
+        Wire::Len(bytes) if field.number == number => {
+            Some(String::from_utf8_lossy(bytes).to_string())
+        }
+        _ => None,
+    })
+}
+
+fn varint_field(fields: &[Field], number: u64) -> Option<u64> {
+    fields.iter().find_map(|field| match field.wire {
+        Wire::Varint(value) if field.number == number => Some(value),
+        _ => None,
+    })
+}
+
+// Numbering from FieldDescriptorProto.Type
+fn descriptor_scalar(kind: u64) -> &'static str {
+    match kind {
+        1 => "double",
+        2 => "float",
+        3 => "int64",
+        4 => "uint64",
+        5 => "int32",
+        6 => "fixed64",
+        7 => "fixed32",
+        8 => "bool",
+        9 => "string",
+        12 => "bytes",
+        13 => "uint32",
+        15 => "sfixed32",
+        16 => "sfixed64",
+        17 => "sint32",
+        18 => "sint64",
+        _ => "",
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<String>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("Unterminated string".to_string());
+            }
+            i += 1;
+            tokens.push(chars[start..i].iter().collect());
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.')) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct ProtoParser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl ProtoParser {
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("Expected '{expected}' but found '{token}'")),
+            None => Err(format!("Expected '{expected}' but the file ended")),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, String> {
+        let token = self.next().unwrap_or_default();
+        let parsed = match token.strip_prefix("0x").or(token.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => token.parse::<u64>(),
+        };
+        parsed.map_err(|_| format!("Expected a field number but found '{token}'"))
+    }
+
+    // Skips to the end of the statement, including a `{ ... }` body if it has one
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+                ";" if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn message(&mut self, scope: &str, schema: &mut Schema) -> Result<(), String> {
+        let name = join(scope, &self.next().ok_or("Expected a message name")?);
+        self.expect("{")?;
+        self.message_body(name, schema)
+    }
+
+    fn message_body(&mut self, name: String, schema: &mut Schema) -> Result<(), String> {
+        schema.order.push(name.clone());
+        let mut definition = MessageDef::default();
+        let mut oneof = false;
+        loop {
+            let token = self
+                .next()
+                .ok_or(format!("Message {name} is missing its closing '}}'"))?;
+            match token.as_str() {
+                "}" if oneof => oneof = false,
+                "}" => break,
+                ";" => {}
+                "message" => self.message(&name, schema)?,
+                "enum" => self.enumeration(&name, schema)?,
+                "option" | "reserved" | "extensions" | "extend" => self.skip_statement(),
+                "oneof" => {
+                    self.next();
+                    self.expect("{")?;
+                    oneof = true;
+                }
+                "map" => {
+                    self.expect("<")?;
+                    let key = self.next().unwrap_or_default();
+                    self.expect(",")?;
+                    let value = self.next().unwrap_or_default();
+                    self.expect(">")?;
+                    let field = self.next().ok_or("Expected a map field name")?;
+                    self.expect("=")?;
+                    let number = self.number()?;
+                    self.skip_statement();
+                    // Maps are encoded as repeated entry messages with a key and value field
+                    let entry = join(&name, &format!("{}Entry", camel_case(&field)));
+                    let mut fields = HashMap::new();
+                    fields.insert(
+                        1,
+                        FieldDef {
+                            name: "key".to_string(),
+                            kind: key,
+                        },
+                    );
+                    fields.insert(
+                        2,
+                        FieldDef {
+                            name: "value".to_string(),
+                            kind: value,
+                        },
+                    );
+                    schema.order.push(entry.clone());
+                    schema.messages.insert(entry.clone(), MessageDef { fields });
+                    definition.fields.insert(
+                        number,
+                        FieldDef {
+                            name: field,
+                            kind: format!(".{entry}"),
+                        },
+                    );
+                }
+                _ => {
+                    let kind = match token.as_str() {
+                        "repeated" | "optional" | "required" => {
+                            self.next().ok_or("Expected a field type")?
+                        }
+                        _ => token,
+                    };
+                    let field = self.next().ok_or("Expected a field name")?;
+                    self.expect("=")?;
+                    let number = self.number()?;
+                    if kind == "group" {
+                        while self.next().is_some_and(|token| token != "{") {}
+                        let group = join(&name, &field);
+                        self.message_body(group.clone(), schema)?;
+                        definition.fields.insert(
+                            number,
+                            FieldDef {
+                                name: field.to_lowercase(),
+                                kind: format!(".{group}"),
+                            },
+                        );
+                        continue;
+                    }
+                    self.skip_statement();
+                    definition
+                        .fields
+                        .insert(number, FieldDef { name: field, kind });
+                }
+            }
+        }
+        schema.messages.insert(name, definition);
+        Ok(())
+    }
+
+    fn enumeration(&mut self, scope: &str, schema: &mut Schema) -> Result<(), String> {
+        let name = join(scope, &self.next().ok_or("Expected an enum name")?);
+        self.expect("{")?;
+        let mut values = HashMap::new();
+        loop {
+            let token = self
+                .next()
+                .ok_or(format!("Enum {name} is missing its closing '}}'"))?;
+            match token.as_str() {
+                "}" => break,
+                ";" => {}
+                "option" | "reserved" => self.skip_statement(),
+                _ => {
+                    self.expect("=")?;
+                    let negative = self.tokens.get(self.pos).is_some_and(|token| token == "-");
+                    if negative {
+                        self.pos += 1;
+                    }
+                    let number = self.number()? as i64;
+                    values.insert(if negative { -number } else { number }, token);
+                    self.skip_statement();
+                }
+            }
+        }
+        schema.enums.insert(name, values);
+        Ok(())
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_DEPTH, parse_message, render};
+
+    fn decode(bytes: &[u8]) -> Result<String, String> {
+        let mut lines = vec![];
+        render(&parse_message(bytes)?, None, None, 0, &mut lines);
+        Ok(lines.join("\n"))
+    }
+
+    #[test]
+    fn schemaless() {
+        // Field 1 = 150, field 2 = "hi", field 3 = { 1: 1 }
+        let bytes = [
+            0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x1a, 0x02, 0x08, 0x01,
+        ];
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            "1: 150 (zigzag: 75)\n2: \"hi\"\n3 {\n  1: 1 (zigzag: -1)\n}"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(decode(&[0x08]).is_err());
+        assert!(decode(&[0x12, 0x05, 0x01]).is_err());
+        assert!(decode(&[0x00, 0x01]).is_err());
+        assert!(decode(&[0x0b, 0x08, 0x01]).is_err());
+        assert!(decode(&[0x0c]).is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_limited() {
+        assert!(decode(&vec![0x0b; 100_000]).is_err());
+
+        let mut message = vec![0x08, 0x01];
+        for _ in 0..30_000 {
+            let mut outer = vec![0x0a];
+            let mut length = message.len();
+            while length >= 0x80 {
+                outer.push((length as u8) | 0x80);
+                length >>= 7;
+            }
+            outer.push(length as u8);
+            outer.extend(message);
+            message = outer;
+        }
+        let tree = decode(&message).unwrap();
+        assert_eq!(
+            tree.lines().filter(|line| line.ends_with('{')).count(),
+            MAX_DEPTH
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::formats::{parse_any, render, render_all};
+use super::jq;
+use super::ndjson::process_lines;
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+pub fn json_query(a: &Args, t: &Term) {
+    let expr_msg = "Enter a JSONPath expression (starting with '$') or a jq filter:";
+    let data_msg =
+        "Enter some JSON, YAML or TOML to query (type 'END' on a new line to stop input):";
+    let (expr, data, raw, ndjson) = match &a.tool {
+        Some(Tools::JSONQuery {
+            expr,
+            data,
+            raw,
+            ndjson,
+        }) => (expr.clone(), data.clone(), *raw, *ndjson),
+        _ => (None, None, false, false),
+    };
+    let expr = expr.unwrap_or_else(|| {
+        print(t, expr_msg);
+        read_input(t, None, None)
+    });
+    let input = data.unwrap_or_else(|| {
+        print(t, data_msg);
+        read_input(t, Some(true), Some("END"))
+    });
+
+    if ndjson {
+        process_lines(t, &input, |value| {
+            Ok(run_query(&expr, &value)?
+                .iter()
+                .map(|result| match result {
+                    Value::String(s) if raw => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect())
+        });
+        return;
+    }
+
+    match _json_query(&expr, &input, raw) {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn _json_query(expr: &str, input: &str, raw: bool) -> Result<String, String> {
+    let (value, format) = parse_any(input)?;
+    let results = run_query(expr, &value)?;
+    if raw {
+        return Ok(results
+            .iter()
+            .map(|value| match value {
+                Value::String(s) => Ok(s.clone()),
+                other => render(other, format),
+            })
+            .collect::<Result<Vec<String>, String>>()?
+            .join("\n"));
+    }
+    render_all(&results, format)
+}
+
+pub fn run_query(expr: &str, value: &Value) -> Result<Vec<Value>, String> {
+    let expr = expr.trim();
+    if expr.starts_with('$') {
+        let path = JsonPath::parse(expr).map_err(|err| format!("Invalid JSONPath: {err}"))?;
+        Ok(path.query(value).all().into_iter().cloned().collect())
+    } else {
+        jq::run(expr, value)
+    }
+}
@@ -0,0 +1,290 @@
+use super::_text_input;
+use super::formats::parse_any;
+use super::percent::{_url_decode, _url_encode};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use serde_json::{Map, Value};
+use url::Url;
+
+pub fn query_to_json(a: &Args, t: &Term) {
+    let msg = "Enter a query string or a URL to convert to JSON:";
+    let data = match &a.tool {
+        Some(Tools::QueryToJSON { data }) => data.clone(),
+        _ => None,
+    };
+    let input = _text_input(t, msg, data);
+    let result = _query_to_json(&input)
+        .and_then(|value| serde_json::to_string_pretty(&value).map_err(|err| err.to_string()));
+    match result {
+        Ok(json_str) => success(t, json_str.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn json_to_query(a: &Args, t: &Term) {
+    let msg = "Enter some JSON, YAML or TOML to convert to a query string (type 'END' on a new line to stop input):";
+    let (data, style, arrays) = match &a.tool {
+        Some(Tools::JSONToQuery {
+            data,
+            style,
+            arrays,
+        }) => (data.clone(), style.clone(), arrays.clone()),
+        _ => (None, "brackets".to_string(), "brackets".to_string()),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    let result = parse_any(&input).and_then(|(value, _)| _json_to_query(&value, &style, &arrays));
+    match result {
+        Ok(query) => success(t, query.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+// A full URL is accepted too, in which case only its query is used
+fn _query_to_json(input: &str) -> Result<Value, String> {
+    let input = input.trim();
+    let query = match Url::parse(input) {
+        Ok(url) if input.contains("://") => url.query().unwrap_or("").to_string(),
+        _ => input.trim_start_matches('?').to_string(),
+    };
+    let mut root = Value::Object(Map::new());
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = _url_decode(key, true)?;
+        let value = _url_decode(value, true)?;
+        insert(&mut root, &key_path(&key), value, &key)?;
+    }
+    // The root stays an object even when its keys are 0, 1, 2...
+    Ok(match root {
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| (key, arrays(value)))
+            .collect(),
+        value => value,
+    })
+}
+
+fn _json_to_query(value: &Value, style: &str, arrays: &str) -> Result<String, String> {
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return Err("Only an object can be converted to a query string".to_string()),
+    };
+    let mut pairs = vec![];
+    for (key, value) in object {
+        flatten(std::slice::from_ref(key), value, style, arrays, &mut pairs);
+    }
+    Ok(pairs.join("&"))
+}
+
+// Splits `filter[a][b]`, `filter.a.b` and mixes like `items[0].id` into their parts. An empty
+// part comes from `[]` and means the value is appended
+fn key_path(key: &str) -> Vec<String> {
+    let mut path = vec![];
+    let mut current = String::new();
+    let mut closed = false;
+    let mut rest = key;
+    while let Some(c) = rest.chars().next() {
+        let starts_key = path.is_empty() && current.is_empty();
+        if c == '['
+            && !starts_key
+            && let Some(end) = rest.find(']')
+        {
+            if !closed {
+                path.push(std::mem::take(&mut current));
+            }
+            path.push(rest[1..end].to_string());
+            rest = &rest[end + 1..];
+            closed = true;
+            continue;
+        }
+        match c {
+            '.' if !closed && !starts_key => path.push(std::mem::take(&mut current)),
+            '.' if closed => {}
+            c => current.push(c),
+        }
+        closed = false;
+        rest = &rest[c.len_utf8()..];
+    }
+    if !closed {
+        path.push(current);
+    }
+    path
+}
+
+// Values of a repeated key are collected into an array
+fn insert(target: &mut Value, path: &[String], value: String, key: &str) -> Result<(), String> {
+    let conflict = || format!("'{key}' mixes up an object, an array and a single value");
+    let Some((first, rest)) = path.split_first() else {
+        return Ok(());
+    };
+    let child = match target {
+        Value::Array(items) if first.is_empty() => {
+            if rest.is_empty() {
+                items.push(Value::String(value));
+                return Ok(());
+            }
+            items.push(container(&rest[0]));
+            items.last_mut().unwrap()
+        }
+        Value::Object(object) if !first.is_empty() || rest.is_empty() => {
+            if rest.is_empty() {
+                match object.get_mut(first) {
+                    None => {
+                        object.insert(first.clone(), Value::String(value));
+                    }
+                    Some(Value::Array(items)) => items.push(Value::String(value)),
+                    Some(existing @ Value::String(_)) => {
+                        *existing = Value::Array(vec![existing.take(), Value::String(value)]);
+                    }
+                    Some(_) => return Err(conflict()),
+                }
+                return Ok(());
+            }
+            object.entry(first.clone()).or_insert(container(&rest[0]))
+        }
+        _ => return Err(conflict()),
+    };
+    match (child, rest[0].is_empty()) {
+        (child @ Value::Array(_), true) | (child @ Value::Object(_), false) => {
+            insert(child, rest, value, key)
+        }
+        // `ids=1&ids[]=2` is a repeated key as well
+        (child @ Value::String(_), true) => {
+            *child = Value::Array(vec![child.take()]);
+            insert(child, rest, value, key)
+        }
+        _ => Err(conflict()),
+    }
+}
+
+fn container(next: &str) -> Value {
+    match next.is_empty() {
+        true => Value::Array(vec![]),
+        false => Value::Object(Map::new()),
+    }
+}
+
+// Objects keyed 0, 1, 2... like `ids[0]=a&ids[1]=b` become arrays
+fn arrays(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            // Keys are sorted as text, so "10" comes before "2"
+            let mut indices: Vec<usize> =
+                object.keys().filter_map(|key| key.parse().ok()).collect();
+            indices.sort();
+            let indexed = !object.is_empty()
+                && indices.len() == object.len()
+                && indices.iter().enumerate().all(|(i, index)| *index == i);
+            let mut entries: Vec<(String, Value)> = object
+                .into_iter()
+                .map(|(key, value)| (key, arrays(value)))
+                .collect();
+            if indexed {
+                entries.sort_by_key(|(key, _)| key.parse::<usize>().unwrap_or(0));
+                Value::Array(entries.into_iter().map(|(_, value)| value).collect())
+            } else {
+                Value::Object(entries.into_iter().collect())
+            }
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(arrays).collect()),
+        value => value,
+    }
+}
+
+// The brackets and dots stay readable, only the key parts and values are encoded
+fn flatten(path: &[String], value: &Value, style: &str, arrays: &str, pairs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let mut child = path.to_vec();
+                child.push(key.clone());
+                flatten(&child, value, style, arrays, pairs);
+            }
+        }
+        Value::Array(items) => {
+            let scalars = items
+                .iter()
+                .all(|item| !item.is_object() && !item.is_array());
+            for (i, item) in items.iter().enumerate() {
+                let mut child = path.to_vec();
+                // Appending with `[]` only works for plain values, nested ones need an index
+                match arrays {
+                    "repeat" if scalars => {}
+                    "brackets" if scalars => child.push("".to_string()),
+                    _ => child.push(i.to_string()),
+                }
+                flatten(&child, item, style, arrays, pairs);
+            }
+        }
+        Value::Null => pairs.push(format!("{}=", key_name(path, style))),
+        Value::String(value) => pairs.push(format!(
+            "{}={}",
+            key_name(path, style),
+            _url_encode(value, "form")
+        )),
+        value => pairs.push(format!("{}={value}", key_name(path, style))),
+    }
+}
+
+fn key_name(path: &[String], style: &str) -> String {
+    let mut name = _url_encode(&path[0], "form");
+    for part in &path[1..] {
+        let part = _url_encode(part, "form");
+        let is_index = part.is_empty() || part.parse::<usize>().is_ok();
+        match style {
+            "dots" if !is_index => name.push_str(&format!(".{part}")),
+            _ => name.push_str(&format!("[{part}]")),
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{_json_to_query, _query_to_json, key_path};
+    use serde_json::json;
+
+    #[test]
+    fn key_paths() {
+        assert_eq!(key_path("filter[a][b]"), ["filter", "a", "b"]);
+        assert_eq!(key_path("filter.a.b"), ["filter", "a", "b"]);
+        assert_eq!(key_path("items[0].id"), ["items", "0", "id"]);
+        assert_eq!(key_path("ids[]"), ["ids", ""]);
+        assert_eq!(key_path("[x]"), ["[x]"]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let cases = [
+            ("filter[a][b]=1&ids[]=x&ids[]=y", "brackets", "brackets"),
+            ("filter.a.b=1&items[0].id=2&items[1].id=3", "dots", "index"),
+            ("tag=a&tag=b&q=hello+world", "brackets", "repeat"),
+            ("0=a&1=b", "brackets", "brackets"),
+        ];
+        for (query, style, arrays) in cases {
+            let value = _query_to_json(query).unwrap();
+            let written = _json_to_query(&value, style, arrays).unwrap();
+            assert_eq!(_query_to_json(&written).unwrap(), value, "{query}");
+        }
+    }
+
+    #[test]
+    fn shapes() {
+        assert_eq!(
+            _query_to_json("https://example.com/?filter.a=1&items[0].id=2&items[1].id=3").unwrap(),
+            json!({"filter": {"a": "1"}, "items": [{"id": "2"}, {"id": "3"}]})
+        );
+        assert_eq!(
+            _query_to_json("a=1&a=2&ids=1&ids[]=2").unwrap(),
+            json!({"a": ["1", "2"], "ids": ["1", "2"]})
+        );
+        // Only nested objects become arrays
+        assert_eq!(
+            _query_to_json("0=a&1=b").unwrap(),
+            json!({"0": "a", "1": "b"})
+        );
+        assert!(_query_to_json("a=1&a[b]=2").is_err());
+    }
+}
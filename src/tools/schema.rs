@@ -0,0 +1,88 @@
+use super::formats::{parse_any, parse_file};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use jsonschema::Draft;
+use std::path::Path;
+
+pub fn json_validate(a: &Args, t: &Term) {
+    let (schema, draft, data) = match &a.tool {
+        Some(Tools::JSONValidate {
+            schema,
+            draft,
+            data,
+        }) => (schema.clone(), draft.clone(), data.clone()),
+        _ => (None, None, None),
+    };
+    let schema = schema.unwrap_or_else(|| {
+        print(t, "Enter the path of the JSON Schema file:");
+        read_input(t, None, None)
+    });
+    let input = data.unwrap_or_else(|| {
+        print(
+            t,
+            "Enter some JSON, YAML or TOML to validate (type 'END' on a new line to stop input):",
+        );
+        read_input(t, Some(true), Some("END"))
+    });
+
+    match _json_validate(&schema, draft.as_deref(), &input) {
+        Ok(violations) if violations.is_empty() => {
+            success(t, "The document is valid", None, a.plain)
+        }
+        Ok(violations) => {
+            error(
+                t,
+                format!("{} violation(s) found", violations.len()).as_str(),
+                Some("Invalid"),
+            );
+            for (instance_path, schema_path, message) in violations {
+                error(
+                    t,
+                    format!("{message} (schema path: {schema_path})").as_str(),
+                    Some(instance_path.as_str()),
+                );
+            }
+        }
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+// Returns the instance path, schema path and message of every violation
+fn _json_validate(
+    schema_path: &str,
+    draft: Option<&str>,
+    input: &str,
+) -> Result<Vec<(String, String, String)>, String> {
+    let (schema, _) = parse_file(schema_path)?;
+    let (instance, _) = parse_any(input)?;
+
+    // Relative `$ref`s to other local schema files are resolved against the schema's own location
+    let base_uri = Path::new(schema_path)
+        .canonicalize()
+        .map_err(|err| format!("Could not resolve {schema_path}: {err}"))?;
+    let mut options = jsonschema::options().with_base_uri(format!("file://{}", base_uri.display()));
+    match draft {
+        Some("7") => options = options.with_draft(Draft::Draft7),
+        Some("2020-12") => options = options.with_draft(Draft::Draft202012),
+        _ => {}
+    }
+    let validator = options
+        .build(&schema)
+        .map_err(|err| format!("Invalid schema: {err}"))?;
+
+    Ok(validator
+        .iter_errors(&instance)
+        .map(|err| {
+            let instance_path = err.instance_path().to_string();
+            (
+                if instance_path.is_empty() {
+                    "/".to_string()
+                } else {
+                    instance_path
+                },
+                format!("#{}", err.schema_path()),
+                err.to_string(),
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,567 @@
+use super::formats::parse_any;
+use super::infer::Shape;
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use std::collections::HashSet;
+
+#[derive(Clone, PartialEq)]
+enum Type {
+    Any,
+    Bool,
+    Integer,
+    Float,
+    String,
+    List(Box<Type>),
+    Struct(String),
+    Union(String, Vec<Type>),
+}
+
+struct Field {
+    key: String,
+    ty: Type,
+    optional: bool,
+    nullable: bool,
+}
+
+struct Struct {
+    name: String,
+    fields: Vec<Field>,
+}
+
+// Includes the keywords that are only reserved for the future or in later editions
+const RUST_KEYWORDS: [&str; 52] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+const PYTHON_KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+const GO_INITIALISMS: [&str; 14] = [
+    "ACL", "API", "CPU", "CSS", "DNS", "HTML", "HTTP", "HTTPS", "ID", "IP", "JSON", "SQL", "URL",
+    "UUID",
+];
+
+pub fn json_to_types(a: &Args, t: &Term) {
+    let msg = "Enter a JSON or YAML sample to generate types from (type 'END' on a new line to stop input):";
+    let (lang, name, typed_dict, data) = match &a.tool {
+        Some(Tools::JSONToTypes {
+            lang,
+            name,
+            typed_dict,
+            data,
+        }) => (lang.clone(), name.clone(), *typed_dict, data.clone()),
+        _ => ("rust".to_string(), "Root".to_string(), false, None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+
+    match _json_to_types(&input, &lang, &name, typed_dict) {
+        Ok(result) => success(t, result.as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+fn _json_to_types(input: &str, lang: &str, name: &str, typed_dict: bool) -> Result<String, String> {
+    let (value, _) = parse_any(input)?;
+    let shape = Shape::from_samples(&[value]);
+    // A list of records is the usual sample, so its items become the root type
+    let root = match &shape.array {
+        Some(items) if shape.object.is_none() => items,
+        _ => &shape,
+    };
+    if root.object.is_none() {
+        return Err("The sample must be an object or an array of objects".to_string());
+    }
+
+    let mut structs = vec![];
+    let mut names = HashSet::new();
+    type_of(root, &pascal_case(name), &mut structs, &mut names);
+
+    Ok(match lang {
+        "ts" => typescript(&structs),
+        "go" => go(&structs),
+        "python" if typed_dict => python_typed_dict(&structs),
+        "python" => python_dataclass(&structs),
+        _ => rust(&structs),
+    })
+}
+
+// Nested structs are pushed before the structs that use them
+fn type_of(
+    shape: &Shape,
+    hint: &str,
+    structs: &mut Vec<Struct>,
+    names: &mut HashSet<String>,
+) -> Type {
+    let mut types = vec![];
+    if let Some(object) = &shape.object {
+        let name = unique_name(hint, names);
+        let fields = object
+            .properties
+            .iter()
+            .map(|(key, property)| Field {
+                key: key.clone(),
+                ty: type_of(property, &pascal_case(key), structs, names),
+                optional: !property.is_required(object),
+                nullable: property.null,
+            })
+            .collect();
+        structs.push(Struct {
+            name: name.clone(),
+            fields,
+        });
+        types.push(Type::Struct(name));
+    }
+    if let Some(items) = &shape.array {
+        let item_type = if items.count == 0 {
+            Type::Any
+        } else {
+            type_of(items, &singular(hint), structs, names)
+        };
+        types.push(Type::List(Box::new(item_type)));
+    }
+    if shape.string.is_some() {
+        types.push(Type::String);
+    }
+    if shape.number {
+        types.push(Type::Float);
+    } else if shape.integer {
+        types.push(Type::Integer);
+    }
+    if shape.boolean {
+        types.push(Type::Bool);
+    }
+    match types.len() {
+        0 => Type::Any,
+        1 => types.remove(0),
+        _ => Type::Union(unique_name(&format!("{hint}Value"), names), types),
+    }
+}
+
+fn unique_name(hint: &str, names: &mut HashSet<String>) -> String {
+    let base = if hint.is_empty() { "Field" } else { hint };
+    let mut name = base.to_string();
+    let mut suffix = 2;
+    while names.contains(&name) {
+        name = format!("{base}{suffix}");
+        suffix += 1;
+    }
+    names.insert(name.clone());
+    name
+}
+
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if ["sses", "xes", "ches", "shes", "zes"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        name[..name.len() - 2].to_string()
+    } else if name.ends_with("ss") {
+        name.to_string()
+    } else if let Some(stem) = name.strip_suffix('s') {
+        stem.to_string()
+    } else {
+        format!("{name}Item")
+    }
+}
+
+// Splits keys like `firstName`, `first_name`, `first-name` and `HTTPServer` into words
+fn words(key: &str) -> Vec<String> {
+    let mut words = vec![];
+    for part in key.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (i, c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_uppercase()
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_numeric()
+                    || chars.get(i + 1).is_some_and(|n| n.is_lowercase()));
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(*c);
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn pascal_case(key: &str) -> String {
+    let name: String = words(key).iter().map(|word| capitalize(word)).collect();
+    if name.starts_with(|c: char| c.is_numeric()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn snake_case(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>()
+        .join("_");
+    if name.is_empty() {
+        "field".to_string()
+    } else if name.starts_with(|c: char| c.is_numeric()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+// JSON string literals are also valid in TypeScript and Python
+fn quote(key: &str) -> String {
+    serde_json::Value::String(key.to_string()).to_string()
+}
+
+fn rust(structs: &[Struct]) -> String {
+    let mut out = vec!["use serde::{Deserialize, Serialize};".to_string()];
+    let mut unions = vec![];
+    for s in structs {
+        let mut lines = vec![
+            "#[derive(Debug, Serialize, Deserialize)]".to_string(),
+            format!("pub struct {} {{", s.name),
+        ];
+        // Keys like `firstName` and `first_name` end up with the same field name
+        let mut taken = HashSet::new();
+        for field in &s.fields {
+            let mut name = unique_name(&snake_case(&field.key), &mut taken);
+            if RUST_KEYWORDS.contains(&name.as_str()) {
+                name = match name.as_str() {
+                    "self" | "Self" | "super" | "crate" => format!("{name}_"),
+                    _ => format!("r#{name}"),
+                };
+            }
+            if name.trim_start_matches("r#") != field.key {
+                lines.push(format!("    #[serde(rename = {:?})]", field.key));
+            }
+            let mut ty = rust_type(&field.ty, &mut unions);
+            if field.optional || field.nullable {
+                ty = format!("Option<{ty}>");
+            }
+            lines.push(format!("    pub {name}: {ty},"));
+        }
+        lines.push("}".to_string());
+        out.push(lines.join("\n"));
+    }
+    let mut seen = HashSet::new();
+    while let Some((name, variants)) = unions.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let mut lines = vec![
+            "#[derive(Debug, Serialize, Deserialize)]".to_string(),
+            "#[serde(untagged)]".to_string(),
+            format!("pub enum {name} {{"),
+        ];
+        for variant in &variants {
+            let variant_name = match variant {
+                Type::Struct(name) | Type::Union(name, _) => name.clone(),
+                Type::List(_) => "List".to_string(),
+                Type::Bool => "Bool".to_string(),
+                Type::Integer => "Integer".to_string(),
+                Type::Float => "Number".to_string(),
+                Type::String => "String".to_string(),
+                Type::Any => "Value".to_string(),
+            };
+            lines.push(format!(
+                "    {variant_name}({}),",
+                rust_type(variant, &mut unions)
+            ));
+        }
+        lines.push("}".to_string());
+        out.push(lines.join("\n"));
+    }
+    out.join("\n\n")
+}
+
+fn rust_type(ty: &Type, unions: &mut Vec<(String, Vec<Type>)>) -> String {
+    match ty {
+        Type::Any => "serde_json::Value".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::Integer => "i64".to_string(),
+        Type::Float => "f64".to_string(),
+        Type::String => "String".to_string(),
+        Type::List(item) => format!("Vec<{}>", rust_type(item, unions)),
+        Type::Struct(name) => name.clone(),
+        Type::Union(name, variants) => {
+            unions.push((name.clone(), variants.clone()));
+            name.clone()
+        }
+    }
+}
+
+fn typescript(structs: &[Struct]) -> String {
+    structs
+        .iter()
+        .map(|s| {
+            let mut lines = vec![format!("export interface {} {{", s.name)];
+            for field in &s.fields {
+                let key = if is_identifier(&field.key) {
+                    field.key.clone()
+                } else {
+                    quote(&field.key)
+                };
+                let mut ty = typescript_type(&field.ty);
+                if field.nullable {
+                    ty = format!("{ty} | null");
+                }
+                let optional = if field.optional { "?" } else { "" };
+                lines.push(format!("  {key}{optional}: {ty};"));
+            }
+            lines.push("}".to_string());
+            lines.join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn typescript_type(ty: &Type) -> String {
+    match ty {
+        Type::Any => "unknown".to_string(),
+        Type::Bool => "boolean".to_string(),
+        Type::Integer | Type::Float => "number".to_string(),
+        Type::String => "string".to_string(),
+        Type::List(item) => match item.as_ref() {
+            Type::Union(..) => format!("({})[]", typescript_type(item)),
+            _ => format!("{}[]", typescript_type(item)),
+        },
+        Type::Struct(name) => name.clone(),
+        Type::Union(_, variants) => variants
+            .iter()
+            .map(typescript_type)
+            .collect::<Vec<String>>()
+            .join(" | "),
+    }
+}
+
+fn go(structs: &[Struct]) -> String {
+    structs
+        .iter()
+        .map(|s| {
+            let mut lines = vec![format!("type {} struct {{", s.name)];
+            let mut taken = HashSet::new();
+            for field in &s.fields {
+                let name: String = words(&field.key)
+                    .iter()
+                    .map(|word| {
+                        let upper = word.to_uppercase();
+                        if GO_INITIALISMS.contains(&upper.as_str()) {
+                            upper
+                        } else {
+                            capitalize(word)
+                        }
+                    })
+                    .collect();
+                let name = if name.starts_with(|c: char| c.is_alphabetic()) {
+                    name
+                } else {
+                    format!("Field{name}")
+                };
+                let name = unique_name(&name, &mut taken);
+                let mut ty = go_type(&field.ty);
+                let mut tag = field.key.clone();
+                if field.optional || field.nullable {
+                    if !ty.starts_with("[]") && ty != "any" {
+                        ty = format!("*{ty}");
+                    }
+                    tag.push_str(",omitempty");
+                }
+                // JSON string escapes are valid in Go too. A raw string can't hold a backtick,
+                // so then the whole tag is quoted instead
+                let tag = format!("json:{}", quote(&tag));
+                let tag = match tag.contains('`') {
+                    true => quote(&tag),
+                    false => format!("`{tag}`"),
+                };
+                lines.push(format!("\t{name} {ty} {tag}"));
+            }
+            lines.push("}".to_string());
+            lines.join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn go_type(ty: &Type) -> String {
+    match ty {
+        Type::Any | Type::Union(..) => "any".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::Integer => "int64".to_string(),
+        Type::Float => "float64".to_string(),
+        Type::String => "string".to_string(),
+        Type::List(item) => format!("[]{}", go_type(item)),
+        Type::Struct(name) => name.clone(),
+    }
+}
+
+fn python_type(ty: &Type) -> String {
+    match ty {
+        Type::Any => "Any".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::Integer => "int".to_string(),
+        Type::Float => "float".to_string(),
+        Type::String => "str".to_string(),
+        Type::List(item) => format!("list[{}]", python_type(item)),
+        Type::Struct(name) => name.clone(),
+        Type::Union(_, variants) => variants
+            .iter()
+            .map(python_type)
+            .collect::<Vec<String>>()
+            .join(" | "),
+    }
+}
+
+fn python_dataclass(structs: &[Struct]) -> String {
+    let mut out = vec!["from dataclasses import dataclass\nfrom typing import Any".to_string()];
+    for s in structs {
+        let mut lines = vec!["@dataclass".to_string(), format!("class {}:", s.name)];
+        // Fields with defaults have to come after the ones without
+        let mut fields: Vec<&Field> = s.fields.iter().collect();
+        fields.sort_by_key(|field| field.optional);
+        let mut taken = HashSet::new();
+        for field in fields {
+            let mut name = snake_case(&field.key);
+            if PYTHON_KEYWORDS.contains(&name.as_str()) {
+                name.push('_');
+            }
+            let name = unique_name(&name, &mut taken);
+            let mut ty = python_type(&field.ty);
+            if field.nullable || field.optional {
+                ty = format!("{ty} | None");
+            }
+            let default = if field.optional { " = None" } else { "" };
+            let comment = if name != field.key {
+                format!("  # {}", quote(&field.key))
+            } else {
+                String::new()
+            };
+            lines.push(format!("    {name}: {ty}{default}{comment}"));
+        }
+        if s.fields.is_empty() {
+            lines.push("    pass".to_string());
+        }
+        out.push(lines.join("\n"));
+    }
+    out.join("\n\n\n")
+}
+
+fn python_typed_dict(structs: &[Struct]) -> String {
+    let mut out = vec!["from typing import Any, NotRequired, TypedDict".to_string()];
+    for s in structs {
+        let field_type = |field: &Field| {
+            let mut ty = python_type(&field.ty);
+            if field.nullable {
+                ty = format!("{ty} | None");
+            }
+            if field.optional {
+                ty = format!("NotRequired[{ty}]");
+            }
+            ty
+        };
+        // Unlike TypeScript, Python doesn't allow '$' in identifiers
+        let class_syntax = s.fields.iter().all(|field| {
+            is_identifier(&field.key)
+                && !field.key.contains('$')
+                && !PYTHON_KEYWORDS.contains(&field.key.as_str())
+        });
+        if class_syntax {
+            let mut lines = vec![format!("class {}(TypedDict):", s.name)];
+            for field in &s.fields {
+                lines.push(format!("    {}: {}", field.key, field_type(field)));
+            }
+            if s.fields.is_empty() {
+                lines.push("    pass".to_string());
+            }
+            out.push(lines.join("\n"));
+        } else {
+            // Keys that aren't identifiers are only allowed with the functional syntax
+            let mut lines = vec![format!("{} = TypedDict(\"{}\", {{", s.name, s.name)];
+            for field in &s.fields {
+                lines.push(format!("    {}: {},", quote(&field.key), field_type(field)));
+            }
+            lines.push("})".to_string());
+            out.push(lines.join("\n"));
+        }
+    }
+    out.join("\n\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::_json_to_types;
+
+    #[test]
+    fn rust_keywords_are_raw_identifiers() {
+        let rust = _json_to_types(
+            r#"{"try": 1, "type": "a", "self": 2}"#,
+            "rust",
+            "Root",
+            false,
+        );
+        let rust = rust.unwrap();
+        assert!(rust.contains("pub r#try: i64,"));
+        assert!(rust.contains("pub r#type: String,"));
+        assert!(rust.contains("#[serde(rename = \"self\")]\n    pub self_: i64,"));
+    }
+
+    #[test]
+    fn colliding_field_names_are_made_unique() {
+        let input = r#"{"firstName": "a", "first_name": "b"}"#;
+        let rust = _json_to_types(input, "rust", "Root", false).unwrap();
+        assert!(rust.contains("pub first_name: String,"));
+        assert!(rust.contains("pub first_name2: String,"));
+        let go = _json_to_types(input, "go", "Root", false).unwrap();
+        assert!(go.contains("FirstName2 string"));
+    }
+
+    #[test]
+    fn go_tags_are_escaped() {
+        let input = r#"{"id": 1, "say \"hi\"": 2, "back\\slash": 3, "tick`": null}"#;
+        let go = _json_to_types(input, "go", "Root", false).unwrap();
+        assert!(go.contains("ID int64 `json:\"id\"`"));
+        assert!(go.contains(r#"`json:"say \"hi\""`"#));
+        assert!(go.contains(r#"`json:"back\\slash"`"#));
+        assert!(go.contains(r#""json:\"tick`,omitempty\"""#));
+    }
+
+    #[test]
+    fn python_typed_dict_falls_back_for_dollar_keys() {
+        let python = _json_to_types(r#"{"$ref": "x"}"#, "python", "Root", true).unwrap();
+        assert!(python.contains("Root = TypedDict(\"Root\", {\n    \"$ref\": str,"));
+    }
+}
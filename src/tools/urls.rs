@@ -0,0 +1,221 @@
+use super::_text_input;
+use super::percent::{_url_decode, _url_encode};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use url::{Host, Url};
+use urlencoding::decode_binary;
+
+pub fn url_parse(a: &Args, t: &Term) {
+    let msg = "Enter a URL to parse:";
+    let data = match &a.tool {
+        Some(Tools::URLParse { data }) => data.clone(),
+        _ => None,
+    };
+    let input = _text_input(t, msg, data);
+    match Url::parse(input.trim()) {
+        Ok(url) => {
+            // The url crate already lowercases the scheme and host, drops the default port and
+            // resolves dot segments
+            success(t, url.as_str(), Some("Normalized"), false);
+            success(t, url.scheme(), Some("Scheme"), false);
+            success(t, url.username(), Some("Username"), false);
+            success(t, url.password().unwrap_or(""), Some("Password"), false);
+            success(t, url.host_str().unwrap_or(""), Some("Host"), false);
+            // Hosts are stored as punycode, so the Unicode form is worked out separately
+            if let Some(Host::Domain(domain)) = url.host() {
+                let (unicode, _) = idna::domain_to_unicode(domain);
+                success(t, unicode.as_str(), Some("Host (Unicode)"), false);
+            }
+            success(t, &optional(url.port()), Some("Port"), false);
+            success(
+                t,
+                &optional(url.port_or_known_default()),
+                Some("Effective Port"),
+                false,
+            );
+            success(
+                t,
+                url.origin().ascii_serialization().as_str(),
+                Some("Origin"),
+                false,
+            );
+            success(t, url.path(), Some("Path"), false);
+            if let Some(segments) = url.path_segments() {
+                for (i, segment) in segments.enumerate() {
+                    let label = format!("Segment {}", i + 1);
+                    success(t, &percent_decoded(segment), Some(label.as_str()), false);
+                }
+            }
+            success(t, url.query().unwrap_or(""), Some("Query"), false);
+            // Every pair is listed so repeated keys and empty values show up
+            for (key, value) in url.query_pairs() {
+                success(t, format!("{key} = {value}").as_str(), Some("Param"), false);
+            }
+            success(t, url.fragment().unwrap_or(""), Some("Fragment"), false);
+            if let Some(fragment) = url.fragment()
+                && fragment.contains('%')
+            {
+                success(
+                    t,
+                    &percent_decoded(fragment),
+                    Some("Fragment (Decoded)"),
+                    false,
+                );
+            }
+        }
+        Err(err) => error(t, err.to_string().as_str(), None),
+    }
+}
+
+pub fn url_edit(a: &Args, t: &Term) {
+    let msg = "Enter a URL to edit:";
+    let (data, mut edits) = match &a.tool {
+        Some(Tools::URLEdit {
+            data,
+            set_param,
+            remove_param,
+            scheme,
+            host,
+            path,
+            join,
+        }) => (
+            data.clone(),
+            Edits {
+                set_param: set_param.clone(),
+                remove_param: remove_param.clone(),
+                scheme: scheme.clone(),
+                host: host.clone(),
+                path: path.clone(),
+                join: join.clone(),
+            },
+        ),
+        _ => (None, Edits::default()),
+    };
+    // When the URL is asked for and there are no options, the parameters to change are asked for
+    // too. A URL given on the command line is only normalized
+    let interactive = data.is_none();
+    let input = _text_input(t, msg, data);
+    if interactive && edits.is_empty() {
+        print(
+            t,
+            "Enter the parameters to set like page=2 or to remove like -token, separated by spaces:",
+        );
+        for edit in read_input(t, None, None).split_whitespace() {
+            match edit.strip_prefix('-') {
+                Some(key) => edits.remove_param.push(key.to_string()),
+                None => edits.set_param.push(edit.to_string()),
+            }
+        }
+    }
+    match _url_edit(&input, &edits) {
+        Ok(url) => success(t, url.as_str(), None, a.plain),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+#[derive(Default)]
+struct Edits {
+    set_param: Vec<String>,
+    remove_param: Vec<String>,
+    scheme: Option<String>,
+    host: Option<String>,
+    path: Option<String>,
+    join: Option<String>,
+}
+
+impl Edits {
+    fn is_empty(&self) -> bool {
+        self.set_param.is_empty()
+            && self.remove_param.is_empty()
+            && self.scheme.is_none()
+            && self.host.is_none()
+            && self.path.is_none()
+            && self.join.is_none()
+    }
+}
+
+// The relative URL is joined first so the other edits apply to the result
+fn _url_edit(input: &str, edits: &Edits) -> Result<String, String> {
+    let mut url = Url::parse(input.trim()).map_err(|err| err.to_string())?;
+    if let Some(relative) = &edits.join {
+        url = url
+            .join(relative)
+            .map_err(|err| format!("Can't join '{relative}': {err}"))?;
+    }
+    if let Some(scheme) = &edits.scheme {
+        url.set_scheme(scheme).map_err(|_| {
+            format!(
+                "Can't change the scheme from '{}' to '{scheme}'",
+                url.scheme()
+            )
+        })?;
+    }
+    if let Some(host) = &edits.host {
+        url.set_host(Some(host))
+            .map_err(|err| format!("Invalid host '{host}': {err}"))?;
+    }
+    if let Some(path) = &edits.path {
+        url.set_path(path);
+    }
+    if !edits.set_param.is_empty() || !edits.remove_param.is_empty() {
+        let set = edits
+            .set_param
+            .iter()
+            .map(|param| {
+                param
+                    .split_once('=')
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .ok_or(format!("Expected KEY=VALUE, got '{param}'"))
+            })
+            .collect::<Result<Vec<(String, String)>, String>>()?;
+        let query = edit_query(url.query(), &set, &edits.remove_param);
+        url.set_query(query.as_deref());
+    }
+    Ok(url.to_string())
+}
+
+// Pairs that aren't edited are kept exactly as they were, so the rest of a signed URL doesn't
+// change because of a different encoding. A parameter that's set keeps its position
+fn edit_query(query: Option<&str>, set: &[(String, String)], remove: &[String]) -> Option<String> {
+    let key_of = |pair: &str| {
+        let key = pair.split('=').next().unwrap_or(pair);
+        _url_decode(key, true).unwrap_or(key.to_string())
+    };
+    let mut pairs: Vec<String> = query
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !remove.contains(&key_of(pair)))
+        .map(String::from)
+        .collect();
+    for (key, value) in set {
+        let encoded = format!(
+            "{}={}",
+            _url_encode(key, "form"),
+            _url_encode(value, "form")
+        );
+        let mut found = false;
+        pairs = pairs
+            .into_iter()
+            .filter_map(|pair| match key_of(&pair) == *key {
+                true if found => None,
+                true => {
+                    found = true;
+                    Some(encoded.clone())
+                }
+                false => Some(pair),
+            })
+            .collect();
+        if !found {
+            pairs.push(encoded);
+        }
+    }
+    (!pairs.is_empty()).then(|| pairs.join("&"))
+}
+
+fn optional(port: Option<u16>) -> String {
+    port.map_or("".to_string(), |port| port.to_string())
+}
+
+fn percent_decoded(input: &str) -> String {
+    String::from_utf8_lossy(&decode_binary(input.as_bytes())).to_string()
+}
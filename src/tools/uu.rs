@@ -0,0 +1,119 @@
+use super::binary::{read_raw, write_decoded};
+use crate::{Args, Tools, error, print, read_input, success};
+use console::Term;
+use std::path::Path;
+
+pub fn uuencode(a: &Args, t: &Term) {
+    let msg = "Enter a string to uuencode:";
+    let (data, file, name) = match &a.tool {
+        Some(Tools::Uuencode { data, file, name }) => (data.clone(), file.clone(), name.clone()),
+        _ => (None, None, None),
+    };
+    // The name in the begin line defaults to the input file's name
+    let name = name.unwrap_or_else(|| {
+        file.as_deref()
+            .and_then(|path| Path::new(path).file_name())
+            .map_or("data".to_string(), |name| {
+                name.to_string_lossy().to_string()
+            })
+    });
+    match read_raw(t, msg, data, file) {
+        Ok(bytes) => success(t, _uuencode(&bytes, &name).as_str(), None, true),
+        Err(err) => error(t, err.as_str(), None),
+    }
+}
+
+pub fn uudecode(a: &Args, t: &Term) {
+    let msg = "Enter some uuencoded data to decode (type 'END' on a new line to stop input):";
+    let (data, hex, out) = match &a.tool {
+        Some(Tools::Uudecode { data, hex, out }) => (data.clone(), *hex, out.clone()),
+        _ => (None, false, None),
+    };
+    let input = data.unwrap_or_else(|| {
+        print(t, msg);
+        read_input(t, Some(true), Some("END"))
+    });
+    let result = _uudecode(&input).and_then(|bytes| write_decoded(t, a.plain, bytes, hex, out));
+    if let Err(err) = result {
+        error(t, err.as_str(), None);
+    }
+}
+
+// A zero is written as '`' rather than a space so lines don't lose trailing characters
+fn symbol(value: u8) -> char {
+    match value & 63 {
+        0 => '`',
+        value => (value + 32) as char,
+    }
+}
+
+fn _uuencode(bytes: &[u8], name: &str) -> String {
+    let mut lines = vec![format!("begin 644 {name}")];
+    for chunk in bytes.chunks(45) {
+        let mut line = String::from(symbol(chunk.len() as u8));
+        for group in chunk.chunks(3) {
+            let mut padded = [0u8; 3];
+            padded[..group.len()].copy_from_slice(group);
+            let [a, b, c] = padded;
+            line.push(symbol(a >> 2));
+            line.push(symbol((a << 4) | (b >> 4)));
+            line.push(symbol((b << 2) | (c >> 6)));
+            line.push(symbol(c));
+        }
+        lines.push(line);
+    }
+    lines.push("`".to_string());
+    lines.push("end".to_string());
+    lines.join("\n")
+}
+
+// The begin and end lines are optional so a bare block of encoded lines can be pasted too
+fn _uudecode(input: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    let mut lines = input.lines().enumerate().peekable();
+    if let Some((_, first)) = lines.peek()
+        && first.starts_with("begin ")
+    {
+        lines.next();
+    }
+    for (index, line) in lines {
+        if line == "end" {
+            break;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let values: Vec<u8> = line
+            .bytes()
+            .map(|c| match c {
+                b'`' => Ok(0),
+                32..=96 => Ok(c - 32),
+                _ => Err(format!(
+                    "Line {}: invalid character '{}'",
+                    index + 1,
+                    c as char
+                )),
+            })
+            .collect::<Result<_, String>>()?;
+        let length = values[0] as usize;
+        if length == 0 {
+            continue;
+        }
+        let mut decoded = vec![];
+        for group in values[1..].chunks(4) {
+            let mut padded = [0u8; 4];
+            padded[..group.len()].copy_from_slice(group);
+            let [a, b, c, d] = padded;
+            decoded.extend([(a << 2) | (b >> 4), (b << 4) | (c >> 2), (c << 6) | d]);
+        }
+        if decoded.len() < length {
+            return Err(format!(
+                "Line {}: expected {length} bytes but the line only holds {}",
+                index + 1,
+                decoded.len()
+            ));
+        }
+        bytes.extend(&decoded[..length]);
+    }
+    Ok(bytes)
+}